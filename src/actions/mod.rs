@@ -7,8 +7,13 @@ pub struct ActionsPlugin;
 pub enum UiAction {
     Up,
     Down,
+    Left,
+    Right,
     Select,
     Start,
+    FiftyFifty,
+    Skip,
+    ExtraTime,
 }
 
 // This plugin listens for keyboard input and converts the input into Actions
@@ -24,14 +29,25 @@ fn spawn_action_system(mut commands: Commands) {
     let mut input_map = InputMap::new([
         (KeyCode::Space, UiAction::Start),
         (KeyCode::Space, UiAction::Select),
+        (KeyCode::Escape, UiAction::Start),
         (KeyCode::Up, UiAction::Up),
         (KeyCode::Down, UiAction::Down),
+        (KeyCode::Left, UiAction::Left),
+        (KeyCode::Right, UiAction::Right),
+        (KeyCode::Key1, UiAction::FiftyFifty),
+        (KeyCode::Key2, UiAction::Skip),
+        (KeyCode::Key3, UiAction::ExtraTime),
     ]);
     input_map.insert(GamepadButtonType::DPadUp, UiAction::Up);
     input_map.insert(GamepadButtonType::DPadDown, UiAction::Down);
+    input_map.insert(GamepadButtonType::DPadLeft, UiAction::Left);
+    input_map.insert(GamepadButtonType::DPadRight, UiAction::Right);
     input_map.insert(GamepadButtonType::South, UiAction::Select);
     input_map.insert(GamepadButtonType::Select, UiAction::Select);
     input_map.insert(GamepadButtonType::Start, UiAction::Start);
+    input_map.insert(GamepadButtonType::West, UiAction::FiftyFifty);
+    input_map.insert(GamepadButtonType::North, UiAction::Skip);
+    input_map.insert(GamepadButtonType::East, UiAction::ExtraTime);
     commands.spawn(InputManagerBundle::<UiAction> {
         // Stores "which actions are currently pressed"
         action_state: ActionState::default(),
@@ -0,0 +1,350 @@
+use crate::actions::UiAction;
+use crate::kiosk::Kiosk;
+use crate::leaderboard::Leaderboard;
+use crate::loading::{FontAssets, QuestionRegistry};
+use crate::prizes;
+use crate::storage;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+use leafwing_input_manager::prelude::ActionState;
+use std::fs;
+use std::path::PathBuf;
+
+/// Seconds the chord has to be held before the admin panel opens
+const HOLD_SECONDS: f32 = 3.;
+
+/// Held together to open the admin panel: `1` and `3` on a keyboard, the west
+/// and east buttons on a gamepad. Neither does anything on the menu.
+const CHORD: [UiAction; 2] = [UiAction::FiftyFifty, UiAction::ExtraTime];
+
+const HIGHLIGHT_COLOR: Color = Color::rgb(1.0, 0.925, 0.153);
+
+pub struct AdminPlugin;
+
+/// A hidden panel for event staff, opened by holding [`CHORD`] on the main menu.
+/// It shows how the event is going and lets staff toggle kiosk mode, choose
+/// the question pack and reset the high scores without restarting the game.
+impl Plugin for AdminPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameStats::load())
+            .add_system(count_game_played.in_schedule(OnEnter(GameState::Playing)))
+            .add_system(count_game_won.in_schedule(OnEnter(GameState::WinScreen)))
+            .add_system(open_admin_panel.in_set(OnUpdate(GameState::Menu)))
+            .add_system(
+                use_admin_panel
+                    .after(open_admin_panel)
+                    .in_set(OnUpdate(GameState::Menu)),
+            )
+            .add_system(close_admin_panel.in_schedule(OnExit(GameState::Menu)));
+    }
+}
+
+/// Games started and won, kept in `stats.ron` in the user's data directory so
+/// the numbers cover the whole event
+#[derive(serde::Serialize, serde::Deserialize, Resource, Default, Debug)]
+pub struct GameStats {
+    pub played: u32,
+    pub won: u32,
+}
+
+impl GameStats {
+    fn path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("stats.ron"))
+    }
+
+    fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return GameStats::default();
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return GameStats::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|error| {
+            warn!("Ignoring game stats in {}: {}", path.display(), error);
+            GameStats::default()
+        })
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|error| error.to_string())
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+                }
+                fs::write(&path, text).map_err(|error| error.to_string())
+            });
+        if let Err(error) = result {
+            error!("Could not save game stats to {}: {}", path.display(), error);
+        }
+    }
+
+    fn win_rate(&self) -> String {
+        if self.played == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.0}%", 100. * self.won as f32 / self.played as f32)
+        }
+    }
+}
+
+fn count_game_played(mut stats: ResMut<GameStats>) {
+    stats.played += 1;
+    stats.save();
+}
+
+fn count_game_won(mut stats: ResMut<GameStats>) {
+    stats.won += 1;
+    stats.save();
+}
+
+/// The settings on the panel, in the order they are listed
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum AdminRow {
+    KioskMode,
+    QuestionPack,
+    ResetLeaderboard,
+    Close,
+}
+
+const ROWS: [AdminRow; 4] = [
+    AdminRow::KioskMode,
+    AdminRow::QuestionPack,
+    AdminRow::ResetLeaderboard,
+    AdminRow::Close,
+];
+
+/// Exists while the admin panel is open; the menu ignores input meanwhile
+#[derive(Resource, Default, Debug)]
+pub struct AdminPanel {
+    selected: usize,
+    /// Reset was chosen once and has to be chosen again to clear the table
+    confirm_reset: bool,
+    cleared: bool,
+}
+
+#[derive(Component)]
+struct AdminPanelRoot;
+
+fn row_label(
+    row: AdminRow,
+    panel: &AdminPanel,
+    kiosk: &Kiosk,
+    registry: &QuestionRegistry,
+) -> String {
+    match row {
+        AdminRow::KioskMode => format!("KIOSK MODE: {}", if kiosk.enabled { "ON" } else { "OFF" }),
+        AdminRow::QuestionPack => format!(
+            "QUESTION PACK: < {} > ({} QUESTIONS)",
+            registry.active_pack().unwrap_or("ALL PACKS"),
+            registry.iter().count()
+        ),
+        AdminRow::ResetLeaderboard if panel.cleared => "HIGH SCORES CLEARED".to_string(),
+        AdminRow::ResetLeaderboard if panel.confirm_reset => {
+            "RESET HIGH SCORES? PRESS AGAIN".to_string()
+        }
+        AdminRow::ResetLeaderboard => "RESET HIGH SCORES".to_string(),
+        AdminRow::Close => "CLOSE".to_string(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn open_admin_panel(
+    mut commands: Commands,
+    actions: Query<&ActionState<UiAction>>,
+    panel: Option<Res<AdminPanel>>,
+    mut chord_handled: Local<bool>,
+    fonts: Res<FontAssets>,
+    stats: Res<GameStats>,
+    kiosk: Res<Kiosk>,
+    registry: Res<QuestionRegistry>,
+) {
+    let held = actions.iter().any(|action_state| {
+        CHORD.iter().all(|action| {
+            action_state.pressed(*action)
+                && action_state.current_duration(*action).as_secs_f32() >= HOLD_SECONDS
+        })
+    });
+    // Opens once per hold, so holding on after closing the panel does not reopen it
+    if !held {
+        *chord_handled = false;
+        return;
+    }
+    if *chord_handled || panel.is_some() {
+        return;
+    }
+    *chord_handled = true;
+    info!("Opening the admin panel");
+
+    let prizes_issued = prizes::issued_count().map_or_else(
+        |error| {
+            warn!("Could not count the claim codes: {}", error);
+            "?".to_string()
+        },
+        |count| count.to_string(),
+    );
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: fonts.crt_font.clone(),
+        font_size,
+        color,
+    };
+    let panel = AdminPanel::default();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    gap: Size::height(Val::Px(12.)),
+                    ..Default::default()
+                },
+                background_color: Color::rgba_u8(22, 23, 26, 240).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            AdminPanelRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "ADMIN",
+                text_style(64., Color::PINK),
+            ));
+            for line in [
+                format!("PRIZES ISSUED: {}", prizes_issued),
+                format!("GAMES PLAYED: {}", stats.played),
+                format!("WIN RATE: {}", stats.win_rate()),
+            ] {
+                parent.spawn(TextBundle::from_section(
+                    line,
+                    text_style(32., Color::WHITE),
+                ));
+            }
+            parent.spawn(NodeBundle {
+                style: Style {
+                    size: Size::height(Val::Px(24.)),
+                    ..default()
+                },
+                ..default()
+            });
+            for (index, row) in ROWS.iter().enumerate() {
+                let color = if index == panel.selected {
+                    HIGHLIGHT_COLOR
+                } else {
+                    Color::GRAY
+                };
+                parent.spawn((
+                    TextBundle::from_section(
+                        row_label(*row, &panel, &kiosk, &registry),
+                        text_style(32., color),
+                    ),
+                    *row,
+                ));
+            }
+            parent.spawn(TextBundle::from_section(
+                "UP/DOWN CHOOSE   LEFT/RIGHT/SELECT CHANGE",
+                text_style(20., Color::GRAY),
+            ));
+        });
+    commands.insert_resource(panel);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn use_admin_panel(
+    mut commands: Commands,
+    actions: Query<&ActionState<UiAction>>,
+    panel: Option<ResMut<AdminPanel>>,
+    mut kiosk: ResMut<Kiosk>,
+    mut registry: ResMut<QuestionRegistry>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut rows: Query<(&mut Text, &AdminRow)>,
+    roots: Query<Entity, With<AdminPanelRoot>>,
+) {
+    let Some(mut panel) = panel else {
+        return;
+    };
+    for action_state in &actions {
+        let selected = ROWS[panel.selected];
+        let step: Option<isize> = if action_state.just_pressed(UiAction::Left) {
+            Some(-1)
+        } else if action_state.just_pressed(UiAction::Right)
+            || action_state.just_pressed(UiAction::Select)
+        {
+            Some(1)
+        } else {
+            None
+        };
+
+        if action_state.just_pressed(UiAction::Up) {
+            panel.selected = panel.selected.saturating_sub(1);
+            panel.confirm_reset = false;
+        } else if action_state.just_pressed(UiAction::Down) {
+            panel.selected = (panel.selected + 1).min(ROWS.len() - 1);
+            panel.confirm_reset = false;
+        } else if let Some(step) = step {
+            match selected {
+                AdminRow::KioskMode => {
+                    kiosk.enabled = !kiosk.enabled;
+                    info!("Kiosk mode {}", if kiosk.enabled { "on" } else { "off" });
+                }
+                AdminRow::QuestionPack => {
+                    // `None` first, for every pack, then each pack on its own
+                    let mut choices: Vec<Option<String>> = vec![None];
+                    choices.extend(
+                        registry
+                            .pack_names()
+                            .into_iter()
+                            .map(|name| Some(name.to_string())),
+                    );
+                    let current = choices
+                        .iter()
+                        .position(|choice| choice.as_deref() == registry.active_pack())
+                        .unwrap_or(0);
+                    let next = (current as isize + step).rem_euclid(choices.len() as isize);
+                    registry.set_active_pack(choices.swap_remove(next as usize));
+                }
+                AdminRow::ResetLeaderboard if step > 0 && !panel.cleared => {
+                    if panel.confirm_reset {
+                        leaderboard.clear();
+                        panel.cleared = true;
+                    }
+                    panel.confirm_reset = !panel.confirm_reset;
+                }
+                AdminRow::ResetLeaderboard => {}
+                AdminRow::Close if action_state.just_pressed(UiAction::Select) => {
+                    info!("Closing the admin panel");
+                    commands.remove_resource::<AdminPanel>();
+                    for root in &roots {
+                        commands.entity(root).despawn_recursive();
+                    }
+                    return;
+                }
+                AdminRow::Close => {}
+            }
+        }
+    }
+
+    if panel.is_changed() || kiosk.is_changed() || registry.is_changed() {
+        for (mut text, row) in &mut rows {
+            text.sections[0].value = row_label(*row, &panel, &kiosk, &registry);
+            text.sections[0].style.color = if ROWS[panel.selected] == *row {
+                HIGHLIGHT_COLOR
+            } else {
+                Color::GRAY
+            };
+        }
+    }
+}
+
+fn close_admin_panel(mut commands: Commands, roots: Query<Entity, With<AdminPanelRoot>>) {
+    commands.remove_resource::<AdminPanel>();
+    for root in &roots {
+        commands.entity(root).despawn_recursive();
+    }
+}
@@ -3,6 +3,7 @@ mod from_component;
 pub use from_component::FromComponentPlugin;
 
 use crate::animation::from_component::FromComponentLabel;
+use crate::LevelState;
 use bevy::prelude::*;
 use std::{marker::PhantomData, ops::Range};
 
@@ -60,6 +61,7 @@ impl Plugin for SpriteSheetAnimationPlugin {
         app.add_event::<AnimationEvent>()
             .add_system(
                 sprite_sheet_animation
+                    .run_if(not(in_state(LevelState::Paused)))
                     .in_set(AnimationLabel)
                     .after(FromComponentLabel),
             )
@@ -0,0 +1,217 @@
+use crate::clock::LevelClock;
+use crate::leaderboard::{self, Leaderboard};
+use crate::levels::clock_for_level;
+use crate::loading::{FontAssets, LevelAssets, Question, QuestionRegistry};
+use crate::player::Lives;
+use crate::random::SessionRng;
+use crate::rules::GameRules;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use rand::prelude::*;
+
+/// Seconds each page of the attract loop, the title screen included, is shown for
+const ATTRACT_SECONDS: f32 = 8.;
+
+pub struct AttractPlugin;
+
+/// Cycles the idle main menu through the title screen, the high scores, how to
+/// play and a sample question, so an unattended kiosk draws players in
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_attract_loop.in_schedule(OnEnter(GameState::Menu)))
+            .add_system(cycle_attract_loop.in_set(OnUpdate(GameState::Menu)))
+            .add_system(cleanup_attract_loop.in_schedule(OnExit(GameState::Menu)));
+    }
+}
+
+/// A page shown over the title screen in turn
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum AttractPage {
+    HighScores,
+    HowToPlay,
+    SampleQuestion,
+}
+
+#[derive(Resource)]
+struct AttractLoop {
+    timer: Timer,
+    /// Pages that have something to show; the title screen comes before the first
+    pages: Vec<AttractPage>,
+    /// `None` while the title screen is shown
+    current: Option<usize>,
+}
+
+fn spawn_page(
+    commands: &mut Commands,
+    page: AttractPage,
+    children: impl FnOnce(&mut ChildBuilder),
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    gap: Size::height(Val::Px(16.)),
+                    ..Default::default()
+                },
+                background_color: Color::rgb_u8(22, 23, 26).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            page,
+        ))
+        .with_children(children);
+}
+
+/// How much of a heart `hearts` is, in words
+fn hearts_text(hearts: f32) -> String {
+    if hearts == 0.5 {
+        "half a heart".to_string()
+    } else if hearts == 1. {
+        "a heart".to_string()
+    } else {
+        format!("{} hearts", hearts)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn setup_attract_loop(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    leaderboard: Res<Leaderboard>,
+    rules: Res<GameRules>,
+    level_assets: Res<LevelAssets>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    level_selection: Res<LevelSelection>,
+    registry: Res<QuestionRegistry>,
+    questions: Res<Assets<Question>>,
+    mut rng: ResMut<SessionRng>,
+) {
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font_assets.crt_font.clone(),
+        font_size,
+        color,
+    };
+    let mut pages = Vec::new();
+    // The level that will be played may set its own time limit
+    let rules_clock = LevelClock::from_rules(&rules);
+    let level_clock = ldtk_assets
+        .get(&level_assets.level)
+        .and_then(|project| project.get_level(&level_selection))
+        .map_or(rules_clock, |level| clock_for_level(level, rules_clock));
+
+    if !leaderboard.entries.is_empty() {
+        spawn_page(&mut commands, AttractPage::HighScores, |parent| {
+            leaderboard::spawn_table(
+                parent,
+                &font_assets,
+                &leaderboard,
+                leaderboard::MAX_ENTRIES,
+                None,
+            );
+        });
+        pages.push(AttractPage::HighScores);
+    }
+
+    spawn_page(&mut commands, AttractPage::HowToPlay, |parent| {
+        parent.spawn(TextBundle::from_section(
+            "HOW TO PLAY",
+            text_style(72., Color::WHITE),
+        ));
+        for line in [
+            format!(
+                "Travel the paths to the {:.0} Ammo Cans",
+                rules.target_score
+            ),
+            "Answer a question at every can".to_string(),
+            format!(
+                "Beat the clock: {:.0}:{:0>2}",
+                (level_clock.time_limit / 60.).floor(),
+                level_clock.time_limit as u32 % 60
+            ),
+            format!(
+                "Wrong answers cost {}",
+                hearts_text(Lives::WRONG_ANSWER_DAMAGE)
+            ),
+        ] {
+            parent.spawn(TextBundle::from_section(
+                line,
+                text_style(40., Color::WHITE),
+            ));
+        }
+    });
+    pages.push(AttractPage::HowToPlay);
+
+    let sample = registry
+        .iter()
+        .filter_map(|(_, handle)| questions.get(handle))
+        .choose(&mut **rng);
+    if let Some(question) = sample {
+        spawn_page(&mut commands, AttractPage::SampleQuestion, |parent| {
+            parent.spawn(TextBundle::from_section(
+                "CAN YOU ANSWER THIS?",
+                text_style(56., Color::PINK),
+            ));
+            parent.spawn(
+                TextBundle::from_section(
+                    question.description.clone(),
+                    text_style(36., Color::WHITE),
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    max_size: Size::new(Val::Px(1000.), Val::Undefined),
+                    ..default()
+                }),
+            );
+            for option in &question.options {
+                parent.spawn(TextBundle::from_section(
+                    option.clone(),
+                    text_style(32., Color::GRAY),
+                ));
+            }
+        });
+        pages.push(AttractPage::SampleQuestion);
+    }
+
+    commands.insert_resource(AttractLoop {
+        timer: Timer::from_seconds(ATTRACT_SECONDS, TimerMode::Repeating),
+        pages,
+        current: None,
+    });
+}
+
+fn cycle_attract_loop(
+    time: Res<Time>,
+    mut attract: ResMut<AttractLoop>,
+    mut pages: Query<(&mut Visibility, &AttractPage)>,
+) {
+    if !attract.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    attract.current = match attract.current {
+        None if !attract.pages.is_empty() => Some(0),
+        Some(index) if index + 1 < attract.pages.len() => Some(index + 1),
+        _ => None,
+    };
+    let shown = attract.current.map(|index| attract.pages[index]);
+    for (mut visibility, page) in &mut pages {
+        *visibility = if Some(*page) == shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn cleanup_attract_loop(mut commands: Commands, pages: Query<Entity, With<AttractPage>>) {
+    commands.remove_resource::<AttractLoop>();
+    for entity in pages.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,73 @@
+//! Checks prize claim codes from the win screen, offline.
+//!
+//! Usage: `redeem-prize [--check] CODE...`. Every valid code that was not
+//! redeemed before is marked as redeemed, unless `--check` is given.
+//! Exits with a non-zero code if any code is invalid or was already redeemed.
+//!
+//! Codes are checked against `FLAMINGO_EVENT_SECRET`, or the secret the game
+//! created on this machine if it is not set. Without either it refuses to check.
+
+use retro_ruffles::prizes::{check_claim_code, EventSecret, Redemption};
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut redeem = true;
+    let mut codes = Vec::new();
+    for arg in env::args().skip(1) {
+        if arg == "--check" {
+            redeem = false;
+        } else {
+            codes.push(arg);
+        }
+    }
+    if codes.is_empty() {
+        eprintln!("usage: redeem-prize [--check] CODE...");
+        return ExitCode::FAILURE;
+    }
+
+    let secret = match EventSecret::load_existing() {
+        Ok(secret) => secret,
+        Err(error) => {
+            eprintln!("error: no event secret: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut ok = true;
+    for code in &codes {
+        match check_claim_code(&secret, code, redeem) {
+            Ok(Redemption::Valid { issued_here }) => {
+                let action = if redeem {
+                    "redeemed"
+                } else {
+                    "not redeemed yet"
+                };
+                let origin = if issued_here {
+                    ""
+                } else {
+                    ", issued on another machine"
+                };
+                println!("{}: OK, {}{}", code, action, origin);
+            }
+            Ok(Redemption::AlreadyRedeemed { at }) => {
+                ok = false;
+                println!("{}: ALREADY REDEEMED (at Unix time {})", code, at);
+            }
+            Ok(Redemption::Invalid) => {
+                ok = false;
+                println!("{}: INVALID", code);
+            }
+            Err(error) => {
+                ok = false;
+                eprintln!("error: {}: {}", code, error);
+            }
+        }
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
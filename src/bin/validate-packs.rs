@@ -0,0 +1,33 @@
+//! Checks question packs without starting the game.
+//!
+//! Usage: `validate-packs [PACK_OR_DIR...]`, defaulting to the packs the game loads.
+//! Exits with a non-zero code if any pack has errors.
+
+use retro_ruffles::validation::{validate_packs, DEFAULT_PACK_DIR};
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut paths: Vec<PathBuf> = env::args_os().skip(1).map(PathBuf::from).collect();
+    if paths.is_empty() {
+        paths.push(PathBuf::from(DEFAULT_PACK_DIR));
+    }
+
+    let report = validate_packs(&paths);
+    for error in &report.errors {
+        eprintln!("error: {}", error);
+    }
+    println!(
+        "Checked {} question(s) in {} pack(s): {} error(s)",
+        report.questions,
+        report.packs,
+        report.errors.len()
+    );
+
+    if report.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,5 +1,6 @@
+use crate::clock::TimeScale;
 use bevy::prelude::*;
-use std::{collections::VecDeque, marker::PhantomData};
+use std::{marker::PhantomData, time::Duration};
 
 pub struct EventSchedulerPlugin<E> {
     data: PhantomData<E>,
@@ -12,7 +13,8 @@ where
     fn build(&self, app: &mut App) {
         app.add_event::<E>()
             .add_system(fire_scheduled_events::<E>)
-            .init_resource::<EventScheduler<E>>();
+            .init_resource::<EventScheduler<E>>()
+            .init_resource::<TimeScale>();
     }
 }
 
@@ -25,12 +27,51 @@ where
     }
 }
 
+impl<E> Default for EventSchedulerPlugin<E>
+where
+    E: 'static + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which time a scheduled event waits for
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum EventClock {
+    /// Real time, from [`Time`]
+    #[default]
+    Real,
+    /// Game time, from [`Time`] multiplied by the [`TimeScale`]. Waits while
+    /// the clock is stopped or running backwards.
+    Scaled,
+}
+
+/// Identifies a scheduled event, to cancel it or ask how long it has left
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ScheduledEventId(u64);
+
+#[derive(Clone, Debug)]
+struct ScheduledEvent<E> {
+    id: ScheduledEventId,
+    event: E,
+    timer: Timer,
+    clock: EventClock,
+    /// Makes the copies sent by a repeating event
+    repeat: Option<fn(&E) -> E>,
+}
+
+/// Sends events of type `E` after a delay. Add an [`EventSchedulerPlugin`] for
+/// `E`, schedule with [`EventScheduler::schedule`] or
+/// [`EventScheduler::schedule_repeating`] and read the events with an
+/// [`EventReader`] as usual.
 #[derive(Clone, Debug, Component, Resource)]
 pub struct EventScheduler<E>
 where
     E: 'static + Send + Sync,
 {
-    events: VecDeque<(E, Timer)>,
+    events: Vec<ScheduledEvent<E>>,
+    next_id: u64,
 }
 
 /// Custom default impl because deriving Default makes it only default when E is default.
@@ -40,40 +81,239 @@ where
 {
     fn default() -> Self {
         EventScheduler::<E> {
-            events: VecDeque::new(),
+            events: Vec::new(),
+            next_id: 0,
         }
     }
 }
 
-// impl<E> EventScheduler<E>
-// where
-//     E: 'static + Send + Sync,
-// {
-//     pub fn schedule(&mut self, event: E, duration: Duration) {
-//         self.events
-//             .push_back((event, Timer::new(duration, TimerMode::Once)));
-//     }
-// }
+impl<E> EventScheduler<E>
+where
+    E: 'static + Send + Sync,
+{
+    fn push(
+        &mut self,
+        event: E,
+        timer: Timer,
+        clock: EventClock,
+        repeat: Option<fn(&E) -> E>,
+    ) -> ScheduledEventId {
+        let id = ScheduledEventId(self.next_id);
+        self.next_id += 1;
+        self.events.push(ScheduledEvent {
+            id,
+            event,
+            timer,
+            clock,
+            repeat,
+        });
+        id
+    }
+
+    /// Sends `event` once, after `delay` has passed on `clock`
+    pub fn schedule(&mut self, event: E, delay: Duration, clock: EventClock) -> ScheduledEventId {
+        self.push(event, Timer::new(delay, TimerMode::Once), clock, None)
+    }
+
+    /// Sends a copy of `event` every `interval` on `clock`, until cancelled
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero, as the event would have to be sent endlessly
+    pub fn schedule_repeating(
+        &mut self,
+        event: E,
+        interval: Duration,
+        clock: EventClock,
+    ) -> ScheduledEventId
+    where
+        E: Clone,
+    {
+        assert!(
+            !interval.is_zero(),
+            "repeating events need an interval above zero"
+        );
+        self.push(
+            event,
+            Timer::new(interval, TimerMode::Repeating),
+            clock,
+            Some(E::clone),
+        )
+    }
+
+    /// Stops `id` from firing, returning its event if it had not fired yet.
+    /// Cancelling an event that already fired does nothing.
+    pub fn cancel(&mut self, id: ScheduledEventId) -> Option<E> {
+        let index = self
+            .events
+            .iter()
+            .position(|scheduled| scheduled.id == id)?;
+        Some(self.events.remove(index).event)
+    }
+
+    /// Cancels every scheduled event
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn is_scheduled(&self, id: ScheduledEventId) -> bool {
+        self.events.iter().any(|scheduled| scheduled.id == id)
+    }
+
+    /// Time on its clock until `id` next fires, `None` once it is done or cancelled
+    pub fn remaining(&self, id: ScheduledEventId) -> Option<Duration> {
+        self.events
+            .iter()
+            .find(|scheduled| scheduled.id == id)
+            .map(|scheduled| scheduled.timer.remaining())
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
 
 pub fn fire_scheduled_events<E>(
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
     mut event_scheduler: ResMut<EventScheduler<E>>,
     mut writer: EventWriter<E>,
 ) where
     E: 'static + Send + Sync,
 {
+    let scaled_delta = time.delta().mul_f32(time_scale.0.max(0.));
     event_scheduler.events = event_scheduler
         .events
         .drain(..)
-        .filter_map(|(event, mut timer)| {
-            timer.tick(time.delta());
-
-            if timer.finished() {
-                writer.send(event);
-                None
-            } else {
-                Some((event, timer))
+        .filter_map(|mut scheduled| {
+            scheduled.timer.tick(match scheduled.clock {
+                EventClock::Real => time.delta(),
+                EventClock::Scaled => scaled_delta,
+            });
+
+            if !scheduled.timer.just_finished() {
+                return Some(scheduled);
+            }
+            match scheduled.repeat {
+                Some(repeat) => {
+                    for _ in 0..scheduled.timer.times_finished_this_tick() {
+                        writer.send(repeat(&scheduled.event));
+                    }
+                    Some(scheduled)
+                }
+                None => {
+                    writer.send(scheduled.event);
+                    None
+                }
             }
         })
         .collect();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Ping;
+
+    fn app() -> App {
+        let mut time = Time::default();
+        time.update_with_instant(time.startup());
+        let mut app = App::new();
+        app.insert_resource(time)
+            .add_plugin(EventSchedulerPlugin::<Ping>::new());
+        app
+    }
+
+    fn scheduler(app: &mut App) -> Mut<EventScheduler<Ping>> {
+        app.world.resource_mut::<EventScheduler<Ping>>()
+    }
+
+    /// Runs one frame `millis` after the last and counts the pings it sent
+    fn advance(app: &mut App, millis: u64) -> usize {
+        let mut time = app.world.resource_mut::<Time>();
+        let last_update = time.last_update().unwrap_or_else(|| time.startup());
+        time.update_with_instant(last_update + Duration::from_millis(millis));
+        app.update();
+        app.world
+            .resource::<Events<Ping>>()
+            .iter_current_update_events()
+            .count()
+    }
+
+    #[test]
+    fn fires_once_after_delay() {
+        let mut app = app();
+        let id = scheduler(&mut app).schedule(Ping, Duration::from_secs(1), EventClock::Real);
+
+        assert_eq!(advance(&mut app, 600), 0);
+        assert_eq!(
+            scheduler(&mut app).remaining(id),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(advance(&mut app, 600), 1);
+        assert!(!scheduler(&mut app).is_scheduled(id));
+        assert_eq!(advance(&mut app, 1000), 0);
+    }
+
+    #[test]
+    fn repeats_every_interval() {
+        let mut app = app();
+        let id =
+            scheduler(&mut app).schedule_repeating(Ping, Duration::from_secs(1), EventClock::Real);
+
+        assert_eq!(advance(&mut app, 1000), 1);
+        assert_eq!(advance(&mut app, 500), 0);
+        // A long frame sends every ping it covered
+        assert_eq!(advance(&mut app, 2500), 3);
+        assert!(scheduler(&mut app).is_scheduled(id));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_zero_interval() {
+        let mut app = app();
+        scheduler(&mut app).schedule_repeating(Ping, Duration::ZERO, EventClock::Real);
+    }
+
+    #[test]
+    fn cancelled_events_do_not_fire() {
+        let mut app = app();
+        let once = scheduler(&mut app).schedule(Ping, Duration::from_secs(1), EventClock::Real);
+        let repeating =
+            scheduler(&mut app).schedule_repeating(Ping, Duration::from_secs(1), EventClock::Real);
+
+        assert_eq!(scheduler(&mut app).cancel(once), Some(Ping));
+        assert_eq!(advance(&mut app, 1000), 1);
+        assert_eq!(scheduler(&mut app).cancel(repeating), Some(Ping));
+        assert_eq!(advance(&mut app, 1000), 0);
+        assert_eq!(scheduler(&mut app).cancel(once), None);
+        assert!(scheduler(&mut app).is_empty());
+    }
+
+    #[test]
+    fn scaled_events_wait_while_the_clock_is_stopped_or_reversed() {
+        let mut app = app();
+        let id = scheduler(&mut app).schedule(Ping, Duration::from_secs(1), EventClock::Scaled);
+        let real = scheduler(&mut app).schedule(Ping, Duration::from_secs(1), EventClock::Real);
+
+        app.insert_resource(TimeScale(0.));
+        assert_eq!(advance(&mut app, 2000), 1);
+        assert!(!scheduler(&mut app).is_scheduled(real));
+        app.insert_resource(TimeScale(-2.));
+        assert_eq!(advance(&mut app, 2000), 0);
+        assert_eq!(
+            scheduler(&mut app).remaining(id),
+            Some(Duration::from_secs(1))
+        );
+
+        app.insert_resource(TimeScale(0.5));
+        assert_eq!(advance(&mut app, 1000), 0);
+        assert_eq!(advance(&mut app, 1000), 1);
+    }
+}
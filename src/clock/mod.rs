@@ -1,8 +1,12 @@
-mod event_scheduler;
+pub mod event_scheduler;
 
-use crate::clock::event_scheduler::EventSchedulerPlugin;
+pub use crate::clock::event_scheduler::{
+    EventClock, EventScheduler, EventSchedulerPlugin, ScheduledEventId,
+};
 use crate::menu::LevelStart;
+use crate::pause::not_paused;
 use crate::player::Vitality;
+use crate::rules::GameRules;
 use crate::{GameState, LevelState};
 use bevy::prelude::*;
 
@@ -11,9 +15,15 @@ pub struct ClockPlugin;
 impl Plugin for ClockPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(EventSchedulerPlugin::<TimeEvent>::new())
-            .insert_resource(TimeScale(1.))
+            .init_resource::<TimeScale>()
             .insert_resource(TimeSinceLevelStart(0.))
-            .add_system(update_time.in_set(OnUpdate(GameState::Playing)));
+            .insert_resource(LevelClock::from_rules(&GameRules::default()))
+            .add_system(reset_level_clock.in_schedule(OnEnter(GameState::Playing)))
+            .add_system(
+                update_time
+                    .run_if(not_paused)
+                    .in_set(OnUpdate(GameState::Playing)),
+            );
     }
 }
 
@@ -31,14 +41,52 @@ pub struct History<T> {
 #[derive(Copy, Clone, PartialEq, Debug, Default, Resource)]
 pub struct TimeSinceLevelStart(pub f32);
 
-#[derive(Copy, Clone, PartialEq, Debug, Default, Resource)]
+/// How fast game time runs against real time. Defaults to real time.
+#[derive(Copy, Clone, PartialEq, Debug, Resource)]
 pub struct TimeScale(pub f32);
 
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale(1.)
+    }
+}
+
+/// The time limit of the level being played. Starts out as the event's
+/// [`GameRules`] and is replaced by the `time_limit` and `warning_at` custom
+/// fields of the LDtk level, where the level sets them.
+#[derive(Copy, Clone, PartialEq, Debug, Resource)]
+pub struct LevelClock {
+    /// Seconds on the clock before the game is lost
+    pub time_limit: f32,
+    /// The clock flashes once this many seconds or fewer are left
+    pub warning_at: f32,
+}
+
+impl LevelClock {
+    pub fn from_rules(rules: &GameRules) -> Self {
+        LevelClock {
+            time_limit: rules.time_limit,
+            warning_at: rules.warning_at,
+        }
+    }
+
+    /// Seconds left on the clock
+    pub fn time_left(&self, time_since_level_start: f32) -> f32 {
+        self.time_limit - time_since_level_start
+    }
+}
+
+fn reset_level_clock(mut level_clock: ResMut<LevelClock>, rules: Res<GameRules>) {
+    *level_clock = LevelClock::from_rules(&rules);
+}
+
 pub enum TimeEvent {
     Normal,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_time(
+    level_clock: Res<LevelClock>,
     mut time_scale: ResMut<TimeScale>,
     mut time_since_level_start: ResMut<TimeSinceLevelStart>,
     bevy_time: Res<Time>,
@@ -63,8 +111,8 @@ pub fn update_time(
         time_since_level_start.0 = 0.;
         time_scale.0 = 0.;
     }
-    if time_since_level_start.0 > 120. {
-        time_since_level_start.0 = 120.;
+    if time_since_level_start.0 > level_clock.time_limit {
+        time_since_level_start.0 = level_clock.time_limit;
         time_scale.0 = 0.;
         for mut vitality in vitals.iter_mut() {
             *vitality = Vitality::Dead;
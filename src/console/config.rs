@@ -0,0 +1,74 @@
+use crate::console::selection::{ActiveSelector, SelectorConfig};
+use crate::lifelines::Lifelines;
+use crate::loading::ConsoleConfigAssets;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+use bevy::reflect::TypeUuid;
+
+/// Per-event settings of the quiz console, loaded from `data/event.console.ron`
+#[derive(serde::Deserialize, TypeUuid, Resource, Clone, Debug)]
+#[uuid = "6b1f3f5e-1d7c-4c59-9a57-2f0d5f4c8e21"]
+pub struct ConsoleConfig {
+    #[serde(default)]
+    pub selector: SelectorConfig,
+    /// Seconds to answer questions that do not set their own `time_limit`,
+    /// `None` for no limit
+    #[serde(default = "default_answer_time")]
+    pub answer_time: Option<f32>,
+    /// Added to the score for an instant correct answer, shrinking to nothing
+    /// as the answer timer runs out
+    #[serde(default = "default_speed_bonus")]
+    pub speed_bonus: f32,
+    /// Lifelines every session starts with
+    #[serde(default)]
+    pub lifelines: Lifelines,
+    /// Seconds given back by the extra time lifeline
+    #[serde(default = "default_extra_time")]
+    pub extra_time: f32,
+    /// Seconds the answer is shown for before play continues on its own
+    #[serde(default = "default_reveal_time")]
+    pub reveal_time: f32,
+}
+
+fn default_answer_time() -> Option<f32> {
+    Some(20.)
+}
+
+fn default_speed_bonus() -> f32 {
+    0.5
+}
+
+fn default_extra_time() -> f32 {
+    15.
+}
+
+fn default_reveal_time() -> f32 {
+    8.
+}
+
+impl Default for ConsoleConfig {
+    fn default() -> Self {
+        ConsoleConfig {
+            selector: SelectorConfig::default(),
+            answer_time: default_answer_time(),
+            speed_bonus: default_speed_bonus(),
+            lifelines: Lifelines::default(),
+            extra_time: default_extra_time(),
+            reveal_time: default_reveal_time(),
+        }
+    }
+}
+
+pub fn apply_console_config(
+    mut commands: Commands,
+    config_assets: Res<ConsoleConfigAssets>,
+    configs: Res<Assets<ConsoleConfig>>,
+) {
+    let config = configs
+        .get(&config_assets.config)
+        .cloned()
+        .unwrap_or_default();
+    info!("Selecting questions with {:?}", config.selector);
+    commands.insert_resource(ActiveSelector::from(&config.selector));
+    commands.insert_resource(config);
+}
@@ -1,41 +1,146 @@
+mod config;
+mod reveal;
+mod selection;
+mod session_log;
 mod systems;
 
+use crate::pause::{not_paused, not_resuming};
 use crate::{GameState, LevelState};
 
-use crate::console::systems::SelectedQuestion;
+use crate::console::systems::{SelectedQuestion, Submission};
 use bevy::prelude::{
-    in_state, Component, IntoSystemAppConfig, IntoSystemConfigs, OnEnter, OnExit, OnUpdate, Plugin,
-    Reflect,
+    in_state, Component, IntoSystemAppConfig, IntoSystemAppConfigs, IntoSystemConfig,
+    IntoSystemConfigs, OnEnter, OnExit, OnUpdate, Plugin, Reflect,
 };
 
+pub use config::ConsoleConfig;
+pub(crate) use reveal::{CORRECT_COLOR, WRONG_COLOR};
+pub use selection::AskedQuestions;
+pub use session_log::LoggedAnswer;
+
 #[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub struct ConsoleStateEntity;
 
+/// Questions with more options than this are laid out in two columns
+const MAX_SINGLE_COLUMN_OPTIONS: usize = 4;
+/// The image of a picture question takes up most of the console, so its
+/// options switch to two columns sooner
+const MAX_SINGLE_COLUMN_OPTIONS_WITH_IMAGE: usize = 2;
+const SINGLE_COLUMN_BUTTON_WIDTH: f32 = 800.;
+const TWO_COLUMN_BUTTON_WIDTH: f32 = 480.;
+
+/// How the answer buttons of the current question are arranged. Options fill the
+/// grid row by row, so option `i` sits at row `i / columns`, column `i % columns`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChoiceLayout {
+    pub options: usize,
+    pub columns: usize,
+}
+
+impl Default for ChoiceLayout {
+    fn default() -> Self {
+        ChoiceLayout::for_options(0)
+    }
+}
+
+impl ChoiceLayout {
+    pub fn for_options(options: usize) -> Self {
+        Self::with_single_column_limit(options, MAX_SINGLE_COLUMN_OPTIONS)
+    }
+
+    /// Layout for the options of a question shown with an image above them
+    pub fn for_image_options(options: usize) -> Self {
+        Self::with_single_column_limit(options, MAX_SINGLE_COLUMN_OPTIONS_WITH_IMAGE)
+    }
+
+    fn with_single_column_limit(options: usize, limit: usize) -> Self {
+        let columns = if options > limit { 2 } else { 1 };
+        ChoiceLayout { options, columns }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.options.div_ceil(self.columns)
+    }
+
+    pub fn button_width(&self) -> f32 {
+        if self.columns > 1 {
+            TWO_COLUMN_BUTTON_WIDTH
+        } else {
+            SINGLE_COLUMN_BUTTON_WIDTH
+        }
+    }
+
+    pub fn slot(&self, index: usize) -> (usize, usize) {
+        (index / self.columns, index % self.columns)
+    }
+
+    pub(crate) fn contains(&self, row: usize, column: usize) -> bool {
+        row * self.columns + column < self.options
+    }
+}
+
 pub struct ConsolePlugin;
 
 impl Plugin for ConsolePlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.register_type::<SelectedQuestion>()
-            .add_system(systems::setup.in_schedule(OnEnter(LevelState::Console)))
-            .add_system(systems::setup_splash.in_schedule(OnEnter(LevelState::Wrong)))
+            .add_event::<Submission>()
+            .init_resource::<ConsoleConfig>()
+            .init_resource::<selection::ActiveSelector>()
+            .init_resource::<AskedQuestions>()
+            .add_system(config::apply_console_config.in_schedule(OnExit(GameState::Loading)))
+            .add_system(
+                systems::setup
+                    .run_if(not_resuming)
+                    .in_schedule(OnEnter(LevelState::Console)),
+            )
+            .add_systems(
+                (reveal::setup_reveal, session_log::record_answer)
+                    .distributive_run_if(not_resuming)
+                    .in_schedule(OnEnter(LevelState::Reveal)),
+            )
             .add_systems(
                 (
                     systems::button_mouse_select,
                     systems::button_keyboard_select,
+                    systems::numeric_step_buttons,
+                    systems::update_answer_labels
+                        .after(systems::numeric_step_buttons)
+                        .after(systems::button_mouse_select)
+                        .after(systems::button_keyboard_select),
+                    systems::answer_countdown,
+                    systems::fit_question_images,
+                    systems::use_lifeline.before(systems::button_keyboard_select),
+                    systems::resolve_submission
+                        .after(systems::button_mouse_select)
+                        .after(systems::button_keyboard_select)
+                        .after(systems::answer_countdown),
                 )
                     .in_set(OnUpdate(LevelState::Console))
                     .distributive_run_if(in_state(GameState::Playing)),
             )
             .add_systems(
-                (systems::countdown,)
-                    .in_set(OnUpdate(LevelState::Wrong))
+                (reveal::reveal_continue,)
+                    .in_set(OnUpdate(LevelState::Reveal))
                     .distributive_run_if(in_state(GameState::Playing)),
             )
             .add_system(
-                systems::destroy_console_state_entities.in_schedule(OnExit(LevelState::Console)),
+                systems::destroy_console_state_entities
+                    .run_if(not_paused)
+                    .in_schedule(OnExit(LevelState::Console)),
             )
             .add_system(
-                systems::destroy_wrong_state_entities.in_schedule(OnExit(LevelState::Wrong)),
+                reveal::destroy_reveal_state_entities
+                    .run_if(not_paused)
+                    .in_schedule(OnExit(LevelState::Reveal)),
+            )
+            // Quitting from the pause menu never exits the state it paused
+            .add_systems(
+                (
+                    systems::destroy_console_state_entities,
+                    reveal::destroy_reveal_state_entities,
+                )
+                    .in_schedule(OnEnter(LevelState::None)),
             );
     }
 }
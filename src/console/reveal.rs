@@ -0,0 +1,354 @@
+use crate::actions::UiAction;
+use crate::console::systems::{format_number, Answer, Submission};
+use crate::console::ConsoleConfig;
+use crate::loading::{FontAssets, Question, QuestionKind, TextureAssets};
+use crate::player::Lives;
+use crate::rules::GameRules;
+use crate::ui::Score;
+use crate::{GameState, LevelState};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+/// Input is ignored for this long after the reveal opens, so the press that
+/// answered the question can not skip it by accident
+const REVEAL_MIN_SECONDS: f32 = 0.75;
+
+pub(crate) const CORRECT_COLOR: Color = Color::rgb(0.0, 0.894, 0.212);
+pub(crate) const WRONG_COLOR: Color = Color::rgb(1.0, 0.0, 0.302);
+const OTHER_COLOR: Color = Color::GRAY;
+
+#[derive(Component)]
+pub struct RevealRootNode;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct RevealTimer(Timer);
+
+/// The question that was just answered, shown on the reveal screen
+#[derive(Resource)]
+pub struct Reveal {
+    pub question: Handle<Question>,
+    pub submission: Submission,
+    pub correct: bool,
+    /// Seconds the player took to answer
+    pub time_taken: f32,
+}
+
+pub fn setup_reveal(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    texture_assets: Res<TextureAssets>,
+    reveal: Res<Reveal>,
+    questions: Res<Assets<Question>>,
+    config: Res<ConsoleConfig>,
+) {
+    commands.insert_resource(RevealTimer(Timer::from_seconds(
+        config.reveal_time,
+        TimerMode::Once,
+    )));
+    let Some(question) = questions.get(&reveal.question) else {
+        return;
+    };
+
+    let (title, title_color) = match (&reveal.submission, reveal.correct) {
+        (_, true) => ("CORRECT!", CORRECT_COLOR),
+        (Submission::TimedOut, false) => ("TIME'S UP", WRONG_COLOR),
+        (Submission::Answered(_), false) => ("WRONG", WRONG_COLOR),
+    };
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font_assets.pixel_font.clone(),
+        font_size,
+        color,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                ..Default::default()
+            },
+            RevealRootNode,
+        ))
+        .with_children(|parent| {
+            // Main box
+            parent
+                .spawn(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(1100.0), Val::Px(600.0)),
+                        justify_content: JustifyContent::SpaceAround,
+                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(30.0)),
+                        ..Default::default()
+                    },
+                    image: texture_assets.menu_background.clone().into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        title,
+                        text_style(48.0, title_color),
+                    ));
+                    parent.spawn(
+                        TextBundle::from_section(
+                            question.description.clone(),
+                            text_style(18.0, Color::WHITE),
+                        )
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            max_size: Size::new(Val::Px(1000.), Val::Undefined),
+                            ..default()
+                        }),
+                    );
+
+                    // Options, the correct ones highlighted
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                gap: Size::height(Val::Px(10.)),
+                                ..Default::default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            for (line, color) in answer_lines(question, &reveal.submission) {
+                                parent
+                                    .spawn(TextBundle::from_section(line, text_style(20.0, color)));
+                            }
+                        });
+
+                    if let Some(explanation) = &question.explanation {
+                        parent.spawn(
+                            TextBundle::from_section(
+                                explanation.clone(),
+                                text_style(16.0, Color::WHITE),
+                            )
+                            .with_text_alignment(TextAlignment::Center)
+                            .with_style(Style {
+                                max_size: Size::new(Val::Px(1000.), Val::Undefined),
+                                ..default()
+                            }),
+                        );
+                    }
+
+                    parent.spawn(TextBundle::from_section(
+                        "Press <A> to continue",
+                        text_style(18.0, Color::WHITE),
+                    ));
+                });
+        });
+}
+
+/// One line per option (or the number for numeric questions) with the colour
+/// it is shown in: correct, wrongly picked by the player, or neither.
+fn answer_lines(question: &Question, submission: &Submission) -> Vec<(String, Color)> {
+    let answer = match submission {
+        Submission::Answered(answer) => Some(answer),
+        Submission::TimedOut => None,
+    };
+    let color = |correct: bool, picked: bool| match (correct, picked) {
+        (true, _) => CORRECT_COLOR,
+        (false, true) => WRONG_COLOR,
+        (false, false) => OTHER_COLOR,
+    };
+
+    match &question.kind {
+        QuestionKind::SingleChoice | QuestionKind::TrueFalse => {
+            let correct = question.answer_index();
+            question
+                .options
+                .iter()
+                .enumerate()
+                .map(|(index, option)| {
+                    let picked = answer == Some(&Answer::Choice(index));
+                    (option.clone(), color(Some(index) == correct, picked))
+                })
+                .collect()
+        }
+        QuestionKind::SelectAll { answers } => question
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let picked =
+                    matches!(answer, Some(Answer::Selection(picks)) if picks.contains(&index));
+                let mark = if answers.contains(option) {
+                    "[X]"
+                } else {
+                    "[ ]"
+                };
+                (
+                    format!("{} {}", mark, option),
+                    color(answers.contains(option), picked),
+                )
+            })
+            .collect(),
+        QuestionKind::Ordering => question
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| (format!("{}. {}", index + 1, option), CORRECT_COLOR))
+            .collect(),
+        QuestionKind::Numeric { step, .. } => {
+            let mut lines = vec![(format!("ANSWER: {}", question.answer), CORRECT_COLOR)];
+            if let Some(Answer::Number(guess)) = answer {
+                let guess = format!("YOUR GUESS: {}", format_number(*guess, *step));
+                lines.push((guess, OTHER_COLOR));
+            }
+            lines
+        }
+    }
+}
+
+/// Moves on once the reveal times out or the player skips it: to the end screen
+/// if that answer won the game or cost the last heart, back to the overworld
+/// after a correct answer, or to another question after a miss. The can is gone
+/// once touched, so a miss has to be made up for at the same can.
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_continue(
+    time: Res<Time>,
+    reveal: Res<Reveal>,
+    mut timer: ResMut<RevealTimer>,
+    actions: Query<&ActionState<UiAction>>,
+    mouse: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    score: Res<Score>,
+    rules: Res<GameRules>,
+    lives: Query<&Lives>,
+    mut level_state: ResMut<NextState<LevelState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    timer.tick(time.delta());
+
+    // Start pauses here, so only select skips
+    let pressed = actions
+        .iter()
+        .any(|action_state| action_state.just_pressed(UiAction::Select))
+        || mouse.just_pressed(MouseButton::Left)
+        || touches.any_just_pressed();
+    let skipped = pressed && timer.elapsed_secs() >= REVEAL_MIN_SECONDS;
+
+    if !timer.finished() && !skipped {
+        return;
+    }
+    if lives.iter().any(Lives::is_empty) {
+        level_state.set(LevelState::OverWorld);
+        game_state.set(GameState::GameOver);
+    } else if score.correct >= rules.target_score {
+        level_state.set(LevelState::OverWorld);
+        game_state.set(GameState::WinScreen);
+    } else if reveal.correct {
+        level_state.set(LevelState::OverWorld);
+    } else {
+        level_state.set(LevelState::Console);
+    }
+}
+
+pub fn destroy_reveal_state_entities(
+    mut commands: Commands,
+    entities_query: Query<Entity, With<RevealRootNode>>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+) {
+    info!("[ConsolePlugin] Destroying reveal state entities before exiting...");
+    commands.remove_resource::<Reveal>();
+    commands.remove_resource::<RevealTimer>();
+    for entity in entities_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    keyboard.clear();
+    info!("[ConsolePlugin] Exiting reveal state")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TimeSinceLevelStart;
+    use crate::console::systems::{resolve_submission, AbilityMenuState};
+    use bevy::asset::AssetPlugin;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Question>()
+            .add_state::<GameState>()
+            .add_state::<LevelState>()
+            .add_event::<Submission>()
+            .init_resource::<Input<MouseButton>>()
+            .init_resource::<Touches>()
+            .init_resource::<ConsoleConfig>()
+            .init_resource::<GameRules>()
+            .init_resource::<Score>()
+            .init_resource::<TimeSinceLevelStart>()
+            .insert_resource(RevealTimer(Timer::from_seconds(0., TimerMode::Once)))
+            .add_system(resolve_submission.in_set(OnUpdate(LevelState::Console)))
+            .add_system(reveal_continue.in_set(OnUpdate(LevelState::Reveal)));
+
+        let question = app.world.resource_mut::<Assets<Question>>().add(Question {
+            description: "Which one is right?".to_string(),
+            kind: QuestionKind::SingleChoice,
+            options: vec!["Right".to_string(), "Wrong".to_string()],
+            answer: "Right".to_string(),
+            category: None,
+            difficulty: None,
+            explanation: None,
+            source: None,
+            tags: Vec::new(),
+            image: None,
+            time_limit: None,
+            used: false,
+        });
+        app.insert_resource(AbilityMenuState {
+            question,
+            ..default()
+        });
+        app.world.spawn(Lives {
+            hearts: GameRules::default().lives,
+        });
+        app.insert_resource(NextState(Some(GameState::Playing)))
+            .insert_resource(NextState(Some(LevelState::Console)));
+        app.update();
+        app
+    }
+
+    /// Answers wrong and lets the reveal run out
+    fn miss(app: &mut App) {
+        app.world
+            .send_event(Submission::Answered(Answer::Choice(1)));
+        for _ in 0..3 {
+            app.update();
+        }
+    }
+
+    fn states(app: &App) -> (GameState, LevelState) {
+        (
+            app.world.resource::<State<GameState>>().0,
+            app.world.resource::<State<LevelState>>().0,
+        )
+    }
+
+    #[test]
+    fn misses_at_one_can_cost_hearts_until_the_game_is_lost() {
+        let mut app = app();
+        let misses = (GameRules::default().lives / Lives::WRONG_ANSWER_DAMAGE) as usize;
+
+        for _ in 1..misses {
+            miss(&mut app);
+            // Another question at the same can
+            assert_eq!(states(&app), (GameState::Playing, LevelState::Console));
+        }
+        miss(&mut app);
+
+        let mut lives = app.world.query::<&Lives>();
+        assert!(lives.single(&app.world).is_empty());
+        assert_eq!(states(&app), (GameState::GameOver, LevelState::OverWorld));
+    }
+}
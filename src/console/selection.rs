@@ -0,0 +1,270 @@
+use crate::console::LoggedAnswer;
+use crate::loading::{Difficulty, Question, QuestionRegistry};
+use crate::random::SessionRng;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+use rand::prelude::*;
+
+/// How questions are picked
+#[derive(serde::Deserialize, Clone, Debug, Default)]
+pub enum SelectorConfig {
+    /// Any unused question, all equally likely
+    #[default]
+    Uniform,
+    Adaptive(AdaptiveConfig),
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct AdaptiveConfig {
+    /// Score from which medium questions are preferred over easy ones
+    #[serde(default = "default_medium_from")]
+    pub medium_from: f32,
+    /// Score from which hard questions are preferred over medium ones
+    #[serde(default = "default_hard_from")]
+    pub hard_from: f32,
+    /// Skip questions of the category that was just asked, if there are others left
+    #[serde(default = "default_true")]
+    pub avoid_repeat_category: bool,
+    /// Ask a missed question once more after this many other questions.
+    /// Missed questions are not asked again if left out.
+    #[serde(default)]
+    pub reask_missed_after: Option<usize>,
+}
+
+fn default_medium_from() -> f32 {
+    2.
+}
+
+fn default_hard_from() -> f32 {
+    4.
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        AdaptiveConfig {
+            medium_from: default_medium_from(),
+            hard_from: default_hard_from(),
+            avoid_repeat_category: default_true(),
+            reask_missed_after: None,
+        }
+    }
+}
+
+/// A question the selector may pick
+pub struct Candidate<'a> {
+    pub handle: &'a Handle<Question>,
+    pub question: &'a Question,
+}
+
+/// Decides which question the console asks next
+pub trait QuestionSelector: Send + Sync {
+    /// `candidates` holds every registered question, including used ones
+    fn select(
+        &self,
+        candidates: &[Candidate],
+        asked: &AskedQuestions,
+        score: f32,
+        rng: &mut SessionRng,
+    ) -> Option<Handle<Question>>;
+}
+
+#[derive(Resource)]
+pub struct ActiveSelector(pub Box<dyn QuestionSelector>);
+
+impl Default for ActiveSelector {
+    fn default() -> Self {
+        ActiveSelector(Box::new(UniformSelector))
+    }
+}
+
+impl From<&SelectorConfig> for ActiveSelector {
+    fn from(config: &SelectorConfig) -> Self {
+        match config {
+            SelectorConfig::Uniform => ActiveSelector(Box::new(UniformSelector)),
+            SelectorConfig::Adaptive(config) => {
+                ActiveSelector(Box::new(AdaptiveSelector(config.clone())))
+            }
+        }
+    }
+}
+
+/// A question asked during the current session
+#[derive(Clone, Debug)]
+pub struct AskedQuestion {
+    pub question: Handle<Question>,
+    pub category: Option<String>,
+    /// `None` until the question has been answered
+    pub answer: Option<LoggedAnswer>,
+}
+
+impl AskedQuestion {
+    pub fn correct(&self) -> Option<bool> {
+        self.answer.as_ref().map(|answer| answer.correct)
+    }
+}
+
+/// Every question asked this session, oldest first. Also the run summary on
+/// the end screen.
+#[derive(Resource, Default, Debug)]
+pub struct AskedQuestions(pub Vec<AskedQuestion>);
+
+impl AskedQuestions {
+    pub fn ask(&mut self, handle: &Handle<Question>, question: &Question) {
+        self.0.push(AskedQuestion {
+            question: handle.clone_weak(),
+            category: question.category.clone(),
+            answer: None,
+        });
+    }
+
+    /// Records the outcome of the question asked last
+    pub fn answer(&mut self, answer: LoggedAnswer) {
+        if let Some(asked) = self.0.last_mut() {
+            asked.answer = Some(answer);
+        }
+    }
+
+    /// The answers given this session, oldest first. Skipped questions have none.
+    pub fn answered(&self) -> impl Iterator<Item = &LoggedAnswer> {
+        self.0.iter().filter_map(|asked| asked.answer.as_ref())
+    }
+
+    fn times_asked(&self, handle: &Handle<Question>) -> usize {
+        self.0
+            .iter()
+            .filter(|asked| asked.question == *handle)
+            .count()
+    }
+}
+
+pub struct UniformSelector;
+
+impl QuestionSelector for UniformSelector {
+    fn select(
+        &self,
+        candidates: &[Candidate],
+        _asked: &AskedQuestions,
+        _score: f32,
+        rng: &mut SessionRng,
+    ) -> Option<Handle<Question>> {
+        candidates
+            .iter()
+            .filter(|candidate| !candidate.question.used)
+            .choose(&mut **rng)
+            .map(|candidate| candidate.handle.clone_weak())
+    }
+}
+
+/// Ramps difficulty up with the score, mixes up categories and can give missed
+/// questions a second chance
+pub struct AdaptiveSelector(pub AdaptiveConfig);
+
+impl AdaptiveSelector {
+    fn target_difficulty(&self, score: f32) -> Difficulty {
+        if score >= self.0.hard_from {
+            Difficulty::Hard
+        } else if score >= self.0.medium_from {
+            Difficulty::Medium
+        } else {
+            Difficulty::Easy
+        }
+    }
+
+    /// The oldest question missed at least `after` questions ago that has not
+    /// been asked a second time yet. Only questions still among `candidates`
+    /// are re-asked, and not straight after one of the same category.
+    fn due_reask(
+        &self,
+        candidates: &[Candidate],
+        asked: &AskedQuestions,
+        last_category: Option<&String>,
+    ) -> Option<Handle<Question>> {
+        let after = self.0.reask_missed_after?;
+        asked
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(index, missed)| {
+                missed.correct() == Some(false)
+                    && asked.0.len() - index > after
+                    && asked.times_asked(&missed.question) == 1
+            })
+            .filter(|(_, missed)| {
+                !self.0.avoid_repeat_category
+                    || last_category.is_none()
+                    || missed.category.as_ref() != last_category
+            })
+            .find_map(|(_, missed)| {
+                candidates
+                    .iter()
+                    .find(|candidate| *candidate.handle == missed.question)
+                    .map(|candidate| candidate.handle.clone_weak())
+            })
+    }
+}
+
+impl QuestionSelector for AdaptiveSelector {
+    fn select(
+        &self,
+        candidates: &[Candidate],
+        asked: &AskedQuestions,
+        score: f32,
+        rng: &mut SessionRng,
+    ) -> Option<Handle<Question>> {
+        let last_category = asked.0.last().and_then(|last| last.category.as_ref());
+        if let Some(handle) = self.due_reask(candidates, asked, last_category) {
+            return Some(handle);
+        }
+
+        let mut pool: Vec<&Candidate> = candidates
+            .iter()
+            .filter(|candidate| !candidate.question.used)
+            .collect();
+
+        if self.0.avoid_repeat_category && last_category.is_some() {
+            let other_categories: Vec<&Candidate> = pool
+                .iter()
+                .copied()
+                .filter(|candidate| candidate.question.category.as_ref() != last_category)
+                .collect();
+            if !other_categories.is_empty() {
+                pool = other_categories;
+            }
+        }
+
+        // Questions without a difficulty count as medium
+        let target = self.target_difficulty(score) as i32;
+        let distance = |candidate: &Candidate| {
+            let difficulty = candidate.question.difficulty.unwrap_or(Difficulty::Medium);
+            (difficulty as i32 - target).abs()
+        };
+        let closest = pool.iter().map(|candidate| distance(candidate)).min()?;
+        pool.into_iter()
+            .filter(|candidate| distance(candidate) == closest)
+            .choose(&mut **rng)
+            .map(|candidate| candidate.handle.clone_weak())
+    }
+}
+
+pub fn select_question(
+    registry: &QuestionRegistry,
+    questions: &Assets<Question>,
+    selector: &ActiveSelector,
+    asked: &AskedQuestions,
+    score: f32,
+    rng: &mut SessionRng,
+) -> Option<Handle<Question>> {
+    let candidates: Vec<Candidate> = registry
+        .iter()
+        .filter_map(|(_key, handle)| {
+            questions
+                .get(handle)
+                .map(|question| Candidate { handle, question })
+        })
+        .collect();
+    selector.0.select(&candidates, asked, score, rng)
+}
@@ -0,0 +1,56 @@
+use crate::console::reveal::Reveal;
+use crate::console::selection::AskedQuestions;
+use crate::console::systems::{format_number, Answer, Submission};
+use crate::loading::{Question, QuestionKind, QuestionRegistry};
+use bevy::prelude::*;
+
+/// How a question of the current session was answered, kept in [`AskedQuestions`]
+#[derive(Clone, Debug)]
+pub struct LoggedAnswer {
+    /// Name of the question in its pack
+    pub key: String,
+    /// What the player answered, as shown on the console
+    pub chosen: String,
+    pub correct: bool,
+    /// Seconds from the question appearing to the answer
+    pub time_taken: f32,
+}
+
+pub fn record_answer(
+    reveal: Res<Reveal>,
+    registry: Res<QuestionRegistry>,
+    questions: Res<Assets<Question>>,
+    mut asked: ResMut<AskedQuestions>,
+) {
+    let Some(question) = questions.get(&reveal.question) else {
+        return;
+    };
+    let key = registry
+        .key(&reveal.question)
+        .unwrap_or("unknown")
+        .to_string();
+    asked.answer(LoggedAnswer {
+        key,
+        chosen: describe_submission(question, &reveal.submission),
+        correct: reveal.correct,
+        time_taken: reveal.time_taken,
+    });
+}
+
+fn describe_submission(question: &Question, submission: &Submission) -> String {
+    let option = |index: &usize| question.options.get(*index).cloned().unwrap_or_default();
+    match submission {
+        Submission::TimedOut => "(no answer)".to_string(),
+        Submission::Answered(Answer::Choice(index)) => option(index),
+        Submission::Answered(Answer::Selection(picks)) => {
+            picks.iter().map(option).collect::<Vec<_>>().join(", ")
+        }
+        Submission::Answered(Answer::Order(picks)) => {
+            picks.iter().map(option).collect::<Vec<_>>().join(" > ")
+        }
+        Submission::Answered(Answer::Number(number)) => match question.kind {
+            QuestionKind::Numeric { step, .. } => format_number(*number, step),
+            _ => number.to_string(),
+        },
+    }
+}
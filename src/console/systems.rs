@@ -1,19 +1,37 @@
 use crate::actions::UiAction;
-use crate::loading::{FontAssets, Question, TextureAssets};
+use crate::clock::TimeSinceLevelStart;
+use crate::console::reveal::Reveal;
+use crate::console::selection::{select_question, ActiveSelector, AskedQuestions};
+use crate::console::ChoiceLayout;
+use crate::console::ConsoleConfig;
+use crate::lifelines::{Lifeline, Lifelines};
+use crate::loading::{FontAssets, Question, QuestionKind, QuestionRegistry, TextureAssets};
+use crate::player::Lives;
+use crate::random::SessionRng;
+use crate::rules::GameRules;
 use crate::ui::Score;
 use crate::{GameState, LevelState};
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 use rand::prelude::*;
 
+/// Room left for the picture of an image question in the 1100x600 main box
+const QUESTION_IMAGE_HEIGHT: f32 = 180.;
+const QUESTION_IMAGE_MAX_WIDTH: f32 = 600.;
+
 #[derive(Component)]
 pub struct UiRootNode;
 
+/// Time left to answer the current question. Absent for questions without a limit.
+#[derive(Resource, Deref, DerefMut)]
+pub struct AnswerTimer(Timer);
+
 #[derive(Component)]
-pub struct WrongRootNode;
+pub struct AnswerTimerText;
 
-#[derive(Resource, Deref, DerefMut)]
-pub struct SplashTimer(Timer);
+/// Seconds left at which the answer timer turns red
+const ANSWER_TIMER_WARNING: f32 = 5.;
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
@@ -21,147 +39,269 @@ pub struct SelectedQuestion {
     question: Handle<Question>,
 }
 
+/// The extra "DONE" button of kinds that need an explicit confirmation
+#[derive(Component)]
+pub struct SubmitButton;
+
+/// The dial showing the current guess of a [`QuestionKind::Numeric`] question
+#[derive(Component)]
+pub struct NumericAnswerText;
+
+/// Clicking or tapping it moves the dial of a [`QuestionKind::Numeric`] question
+/// by this many steps
+#[derive(Component)]
+pub struct NumericStepButton(f64);
+
+/// The picture above the options of a question with an image
+#[derive(Component)]
+pub struct QuestionImage;
+
 #[derive(Reflect, Component, Default, PartialEq, Eq, Clone)]
 #[reflect(Component)]
 pub struct BtnGridPos {
     pub row: usize,
+    pub column: usize,
     pub choice: String,
+    /// Index into [`Question::options`] of the option this button stands for,
+    /// `None` for the DONE button
+    pub option: Option<usize>,
 }
 
 impl BtnGridPos {
-    pub fn new(row: usize, choice: String) -> Self {
-        Self { row, choice }
+    pub fn new(row: usize, column: usize, choice: String, option: Option<usize>) -> Self {
+        Self {
+            row,
+            column,
+            choice,
+            option,
+        }
+    }
+
+    pub fn same_slot(&self, other: &BtnGridPos) -> bool {
+        self.row == other.row && self.column == other.column
     }
 }
 
 #[derive(Default, Resource)]
 pub struct AbilityMenuState {
     pub selected_pos: BtnGridPos,
+    pub layout: ChoiceLayout,
+    pub question: Handle<Question>,
+    pub kind: QuestionKind,
+    /// Indices of the options toggled so far by select-all and ordering questions,
+    /// in pick order
+    pub picks: Vec<usize>,
+    /// Current guess of a numeric question
+    pub number: f64,
+    /// Options taken out by the 50/50 lifeline
+    pub eliminated: Vec<usize>,
+    /// [`Time::elapsed_seconds`] when the question appeared
+    pub asked_at: f32,
 }
 
-pub fn setup_splash(
-    mut commands: Commands,
-    font_assets: Res<FontAssets>,
-    texture_assets: Res<TextureAssets>,
-) {
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..Default::default()
-                },
-                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
-                ..Default::default()
-            },
-            WrongRootNode,
-        ))
-        .with_children(|parent| {
-            // Main box
-            parent
-                .spawn(ImageBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(1100.0), Val::Px(600.0)),
-                        justify_content: JustifyContent::FlexStart,
-                        align_items: AlignItems::Center,
-                        flex_direction: FlexDirection::Column,
-                        ..Default::default()
-                    },
-                    image: texture_assets.menu_background.clone().into(),
-                    ..Default::default()
+impl AbilityMenuState {
+    fn new(question: Handle<Question>, kind: QuestionKind, layout: ChoiceLayout) -> Self {
+        let number = match kind {
+            QuestionKind::Numeric { min, max, step, .. } => {
+                min + ((max - min) / 2. / step).round() * step
+            }
+            _ => 0.,
+        };
+        AbilityMenuState {
+            layout,
+            question,
+            kind,
+            number,
+            ..default()
+        }
+    }
+
+    fn toggle_pick(&mut self, option: usize) {
+        if let Some(index) = self.picks.iter().position(|pick| *pick == option) {
+            self.picks.remove(index);
+        } else {
+            self.picks.push(option);
+        }
+    }
+
+    fn adjust_number(&mut self, steps: f64) {
+        if let QuestionKind::Numeric { min, max, step, .. } = self.kind {
+            self.number = (self.number + steps * step).clamp(min, max);
+        }
+    }
+
+    /// Presses the button of `option`, `None` being the DONE button. Returns the
+    /// answer once the press completes one.
+    fn activate(&mut self, option: Option<usize>) -> Option<Answer> {
+        match (&self.kind, option) {
+            (QuestionKind::Numeric { .. }, _) => Some(Answer::Number(self.number)),
+            (_, Some(option)) if self.eliminated.contains(&option) => None,
+            (QuestionKind::SelectAll { .. }, None) => Some(Answer::Selection(self.picks.clone())),
+            (QuestionKind::SelectAll { .. }, Some(option)) => {
+                self.toggle_pick(option);
+                None
+            }
+            (QuestionKind::Ordering, Some(option)) => {
+                self.toggle_pick(option);
+                (self.picks.len() == self.layout.options).then(|| Answer::Order(self.picks.clone()))
+            }
+            (_, Some(option)) => Some(Answer::Choice(option)),
+            (_, None) => None,
+        }
+    }
+
+    fn move_down(&mut self) {
+        let rows = self.layout.rows().max(1);
+        let pos = &mut self.selected_pos;
+        pos.row = (pos.row + 1) % rows;
+        if !self.layout.contains(pos.row, pos.column) {
+            pos.row = 0;
+        }
+    }
+
+    fn move_up(&mut self) {
+        let rows = self.layout.rows().max(1);
+        let pos = &mut self.selected_pos;
+        pos.row = if pos.row == 0 { rows - 1 } else { pos.row - 1 };
+        if !self.layout.contains(pos.row, pos.column) {
+            pos.row = pos.row.saturating_sub(1);
+        }
+    }
+
+    /// Moves one column right for a positive `step`, left for a negative one
+    fn move_sideways(&mut self, step: isize) {
+        let columns = self.layout.columns.max(1) as isize;
+        let pos = &mut self.selected_pos;
+        pos.column = (pos.column as isize + step).rem_euclid(columns) as usize;
+        if !self.layout.contains(pos.row, pos.column) {
+            pos.column = 0;
+        }
+    }
+}
+
+/// How the current question was left: answered, or out of time
+#[derive(Debug, Clone, PartialEq)]
+pub enum Submission {
+    Answered(Answer),
+    TimedOut,
+}
+
+/// What the player submitted for the current question. Options are referred to by
+/// their index in [`Question::options`], whatever order they were shown in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Choice(usize),
+    Selection(Vec<usize>),
+    Order(Vec<usize>),
+    Number(f64),
+}
+
+fn grade(question: &Question, answer: &Answer) -> bool {
+    match (&question.kind, answer) {
+        (QuestionKind::SingleChoice | QuestionKind::TrueFalse, Answer::Choice(choice)) => {
+            question.answer_index() == Some(*choice)
+        }
+        (QuestionKind::SelectAll { answers }, Answer::Selection(picks)) => {
+            picks.len() == answers.len()
+                && picks.iter().all(|pick| {
+                    question
+                        .options
+                        .get(*pick)
+                        .is_some_and(|option| answers.contains(option))
                 })
-                .with_children(|parent| {
-                    // Title text wrapper
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                size: Size::new(Val::Percent(100.0), Val::Px(110.0)),
-                                justify_content: JustifyContent::SpaceAround,
-                                align_items: AlignItems::Center,
-                                flex_direction: FlexDirection::Row,
-                                ..Default::default()
-                            },
-                            background_color: Color::NONE.into(),
-                            ..Default::default()
-                        })
-                        .with_children(|parent| {
-                            // Title text
-                            parent.spawn(
-                                TextBundle::from_section(
-                                    "WRONG",
-                                    TextStyle {
-                                        font: font_assets.pixel_font.clone(),
-                                        font_size: 96.0,
-                                        color: Color::RED,
-                                    },
-                                )
-                                .with_text_alignment(TextAlignment::Center)
-                                .with_style(Style {
-                                    position_type: PositionType::Absolute,
-                                    position: UiRect {
-                                        top: Val::Px(240.0),
-                                        ..default()
-                                    },
-                                    max_size: Size {
-                                        width: Val::Px(1000.),
-                                        height: Val::Undefined,
-                                    },
-                                    ..default()
-                                }),
-                            );
-                        });
-                });
-        });
+        }
+        (QuestionKind::Ordering, Answer::Order(picks)) => {
+            picks.iter().copied().eq(0..question.options.len())
+        }
+        (QuestionKind::Numeric { tolerance, .. }, Answer::Number(value)) => question
+            .answer
+            .trim()
+            .parse::<f64>()
+            .is_ok_and(|correct| (correct - value).abs() <= *tolerance + f64::EPSILON),
+        _ => false,
+    }
+}
 
-    commands.insert_resource(SplashTimer(Timer::from_seconds(2.0, TimerMode::Once)));
+pub fn format_number(value: f64, step: f64) -> String {
+    let decimals = step
+        .to_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    format!("{:.*}", decimals, value)
 }
 
-pub fn countdown(
-    mut level_state: ResMut<NextState<LevelState>>,
-    time: Res<Time>,
-    mut timer: ResMut<SplashTimer>,
-) {
-    if timer.tick(time.delta()).finished() {
-        level_state.set(LevelState::Console);
+fn help_text(kind: &QuestionKind) -> &'static str {
+    match kind {
+        QuestionKind::SingleChoice | QuestionKind::TrueFalse => "Use arrow keys & <A> to select",
+        QuestionKind::SelectAll { .. } => "Toggle every answer that applies, then DONE",
+        QuestionKind::Ordering => "Select the answers in order, first to last",
+        QuestionKind::Numeric { .. } => "Up/down to adjust, left/right x10, <A> to submit",
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup(
     mut commands: Commands,
     font_assets: Res<FontAssets>,
     texture_assets: Res<TextureAssets>,
     mut input: ResMut<Input<KeyCode>>,
-    mut questions: ResMut<Assets<Question>>,
+    registry: Res<QuestionRegistry>,
+    questions: Res<Assets<Question>>,
+    images: Res<Assets<Image>>,
+    mut rng: ResMut<SessionRng>,
+    selector: Res<ActiveSelector>,
+    mut asked: ResMut<AskedQuestions>,
+    score: Res<Score>,
+    config: Res<ConsoleConfig>,
     mut game_state: ResMut<NextState<GameState>>,
     mut level_state: ResMut<NextState<LevelState>>,
+    time: Res<Time>,
 ) {
     input.clear(); // clear any `just_pressed` events that may be left over from previous state
-    commands.insert_resource(AbilityMenuState::default());
-
-    let button_style = Style {
-        size: Size::new(Val::Px(800.0), Val::Px(75.0)),
-        // center button
-        margin: UiRect {
-            left: Val::Auto,
-            right: Val::Auto,
-            top: Val::Px(10.0),
-            bottom: Val::Px(10.0),
-        },
-        // horizontally center child text
-        justify_content: JustifyContent::Center,
-        // vertically center child text
-        align_items: AlignItems::Center,
-        ..Default::default()
-    };
 
-    match questions
-        .iter_mut()
-        .filter(|(_idx, q)| !q.used)
-        .choose(&mut thread_rng())
-    {
-        Some((id, picked)) => {
+    let selected = select_question(
+        &registry,
+        &questions,
+        &selector,
+        &asked,
+        score.correct,
+        &mut rng,
+    );
+    match selected.and_then(|handle| questions.get(&handle).map(|q| (handle, q))) {
+        Some((handle, picked)) => {
+            asked.ask(&handle, picked);
+            let time_limit = picked.time_limit.or(config.answer_time);
+            // Shuffled anew on every presentation; buttons keep the index of their option
+            let mut options: Vec<(usize, String)> =
+                picked.options.iter().cloned().enumerate().collect();
+            if picked.kind != QuestionKind::TrueFalse {
+                options.shuffle(&mut **rng);
+            }
+            let slots = options.len() + usize::from(picked.kind.has_submit_button());
+            let layout = if picked.image.is_some() {
+                ChoiceLayout::for_image_options(slots)
+            } else {
+                ChoiceLayout::for_options(slots)
+            };
+            let mut state = AbilityMenuState::new(handle.clone_weak(), picked.kind.clone(), layout);
+            state.asked_at = time.elapsed_seconds();
+
+            let button_style = Style {
+                size: Size::new(Val::Px(layout.button_width()), Val::Px(75.0)),
+                // center button
+                margin: UiRect {
+                    left: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                },
+                // horizontally center child text
+                justify_content: JustifyContent::Center,
+                // vertically center child text
+                align_items: AlignItems::Center,
+                ..Default::default()
+            };
+
             commands
                 .spawn(NodeBundle {
                     style: Style {
@@ -232,6 +372,10 @@ pub fn setup(
                                     );
                                 });
 
+                            if let Some(image) = &picked.image {
+                                spawn_question_image(parent, image, &images);
+                            }
+
                             // Buttons wrapper
                             parent
                                 .spawn(NodeBundle {
@@ -250,7 +394,7 @@ pub fn setup(
                                     parent
                                         .spawn(NodeBundle {
                                             style: Style {
-                                                size: Size::new(Val::Percent(50.0), Val::Auto),
+                                                size: Size::new(Val::Percent(100.0), Val::Auto),
                                                 justify_content: JustifyContent::Center,
                                                 align_items: AlignItems::Center,
                                                 margin: UiRect {
@@ -266,53 +410,14 @@ pub fn setup(
                                             ..Default::default()
                                         })
                                         .with_children(|parent| {
-                                            parent.spawn(TextBundle::from_section(
-                                                "CHOICES:",
-                                                TextStyle {
-                                                    font: font_assets.pixel_font.clone(),
-                                                    font_size: 20.0,
-                                                    color: Color::WHITE,
-                                                },
-                                            ));
-
-                                            // Answer choices
-                                            let mut options = picked.clone().options;
-                                            options.shuffle(&mut thread_rng());
-
-                                            for (pos, choice) in options.iter().enumerate() {
-                                                parent
-                                                    .spawn((
-                                                        ButtonBundle {
-                                                            style: button_style.clone(),
-                                                            background_color: Color::rgb(
-                                                                0.15, 0.15, 0.15,
-                                                            )
-                                                            .into(),
-                                                            image: texture_assets
-                                                                .button
-                                                                .clone()
-                                                                .into(),
-                                                            ..Default::default()
-                                                        },
-                                                        BtnGridPos::new(pos, choice.clone()),
-                                                        SelectedQuestion {
-                                                            question: Handle::weak(id),
-                                                        },
-                                                        Name::new("Choice Slot"),
-                                                    ))
-                                                    .with_children(|parent| {
-                                                        parent.spawn(TextBundle::from_section(
-                                                            choice,
-                                                            TextStyle {
-                                                                font: font_assets
-                                                                    .pixel_font
-                                                                    .clone(),
-                                                                font_size: 20.0,
-                                                                color: Color::WHITE,
-                                                            },
-                                                        ));
-                                                    });
-                                            }
+                                            spawn_answer_area(
+                                                parent,
+                                                &font_assets,
+                                                &texture_assets,
+                                                &state,
+                                                &options,
+                                                button_style,
+                                            );
                                         });
                                 });
 
@@ -336,17 +441,35 @@ pub fn setup(
                                 })
                                 .with_children(|parent| {
                                     parent.spawn(TextBundle::from_section(
-                                        "Use arrow keys & <A> to select",
+                                        help_text(&state.kind),
                                         TextStyle {
                                             font: font_assets.pixel_font.clone(),
                                             font_size: 18.0,
                                             color: Color::WHITE,
                                         },
                                     ));
+                                    if let Some(seconds) = time_limit {
+                                        parent.spawn((
+                                            TextBundle::from_section(
+                                                format!("TIME {:.0}", seconds.ceil()),
+                                                TextStyle {
+                                                    font: font_assets.pixel_font.clone(),
+                                                    font_size: 18.0,
+                                                    color: Color::WHITE,
+                                                },
+                                            ),
+                                            AnswerTimerText,
+                                        ));
+                                    }
                                 });
                         });
                 })
                 .insert(UiRootNode);
+            commands.insert_resource(state);
+            if let Some(seconds) = time_limit {
+                commands
+                    .insert_resource(AnswerTimer(Timer::from_seconds(seconds, TimerMode::Once)));
+            }
         }
         None => {
             level_state.set(LevelState::OverWorld);
@@ -355,12 +478,273 @@ pub fn setup(
     }
 }
 
+/// Spawns the picture of an image question, scaled to fit between the question
+/// text and the answer buttons.
+fn spawn_question_image(parent: &mut ChildBuilder, image: &Handle<Image>, images: &Assets<Image>) {
+    // An image that is still loading takes the full box until `fit_question_images` sizes it
+    let size = images.get(image).map_or(
+        Size::new(
+            Val::Px(QUESTION_IMAGE_MAX_WIDTH),
+            Val::Px(QUESTION_IMAGE_HEIGHT),
+        ),
+        question_image_size,
+    );
+
+    parent.spawn((
+        ImageBundle {
+            style: Style {
+                size,
+                margin: UiRect {
+                    left: Val::Auto,
+                    right: Val::Auto,
+                    top: Val::Px(0.0),
+                    bottom: Val::Px(10.0),
+                },
+                ..Default::default()
+            },
+            image: image.clone().into(),
+            ..Default::default()
+        },
+        QuestionImage,
+    ));
+}
+
+/// Scales `image` to fit the box above the options, keeping its aspect ratio
+fn question_image_size(image: &Image) -> Size {
+    let Vec2 { x, y } = image.size();
+    let scale = (QUESTION_IMAGE_MAX_WIDTH / x).min(QUESTION_IMAGE_HEIGHT / y);
+    Size::new(Val::Px(x * scale), Val::Px(y * scale))
+}
+
+/// Resizes a question image that was still loading when the console opened
+pub fn fit_question_images(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut nodes: Query<(&mut Style, &UiImage), With<QuestionImage>>,
+) {
+    for event in events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else {
+            continue;
+        };
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+        for (mut style, ui_image) in &mut nodes {
+            if ui_image.texture == *handle {
+                style.size = question_image_size(image);
+            }
+        }
+    }
+}
+
+/// Spawns the "CHOICES:" heading and whatever the player answers with: a grid of
+/// buttons (plus DONE where needed) or, for numeric questions, the number dial.
+fn spawn_answer_area(
+    parent: &mut ChildBuilder,
+    font_assets: &FontAssets,
+    texture_assets: &TextureAssets,
+    state: &AbilityMenuState,
+    options: &[(usize, String)],
+    button_style: Style,
+) {
+    if let QuestionKind::Numeric { step, .. } = state.kind {
+        parent.spawn(TextBundle::from_section(
+            "ANSWER:",
+            TextStyle {
+                font: font_assets.pixel_font.clone(),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        ));
+        parent.spawn((
+            TextBundle::from_section(
+                format!("< {} >", format_number(state.number, step)),
+                TextStyle {
+                    font: font_assets.pixel_font.clone(),
+                    font_size: 48.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(30.0)),
+                ..default()
+            }),
+            NumericAnswerText,
+        ));
+
+        // The same moves as the arrow keys, for mice and touch screens
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                background_color: Color::NONE.into(),
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                for (label, steps) in [("-10", -10.), ("-", -1.), ("+", 1.), ("+10", 10.)] {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(120.0), button_style.size.height),
+                                    ..button_style.clone()
+                                },
+                                background_color: Color::rgb(0.15, 0.15, 0.15).into(),
+                                image: texture_assets.button.clone().into(),
+                                ..Default::default()
+                            },
+                            NumericStepButton(steps),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font: font_assets.pixel_font.clone(),
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+                }
+            });
+        spawn_choice_button(
+            parent,
+            font_assets,
+            texture_assets,
+            button_style,
+            BtnGridPos::new(0, 0, "SUBMIT".to_string(), None),
+            state.question.clone(),
+        )
+        .insert(SubmitButton);
+        return;
+    }
+
+    parent.spawn(TextBundle::from_section(
+        "CHOICES:",
+        TextStyle {
+            font: font_assets.pixel_font.clone(),
+            font_size: 20.0,
+            color: Color::WHITE,
+        },
+    ));
+
+    let layout = state.layout;
+    let submit = state.kind.has_submit_button().then_some("DONE".to_string());
+    let slots: Vec<(String, Option<usize>)> = options
+        .iter()
+        .map(|(index, option)| (option.clone(), Some(*index)))
+        .chain(submit.map(|label| (label, None)))
+        .collect();
+
+    // Answer choices, one column node per layout column
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            background_color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for column in 0..layout.columns {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            ..Default::default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for (pos, (choice, option)) in slots
+                            .iter()
+                            .enumerate()
+                            .filter(|(pos, _)| layout.slot(*pos).1 == column)
+                        {
+                            let (row, column) = layout.slot(pos);
+                            let mut button = spawn_choice_button(
+                                parent,
+                                font_assets,
+                                texture_assets,
+                                button_style.clone(),
+                                BtnGridPos::new(row, column, choice.clone(), *option),
+                                state.question.clone(),
+                            );
+                            if option.is_none() {
+                                button.insert(SubmitButton);
+                            }
+                        }
+                    });
+            }
+        });
+}
+
+fn spawn_choice_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    font_assets: &FontAssets,
+    texture_assets: &TextureAssets,
+    style: Style,
+    grid_pos: BtnGridPos,
+    question: Handle<Question>,
+) -> EntityCommands<'w, 's, 'a> {
+    let label = grid_pos.choice.clone();
+    let mut button = parent.spawn((
+        ButtonBundle {
+            style,
+            background_color: Color::rgb(0.15, 0.15, 0.15).into(),
+            image: texture_assets.button.clone().into(),
+            ..Default::default()
+        },
+        grid_pos,
+        SelectedQuestion { question },
+        Name::new("Choice Slot"),
+    ));
+    button.with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font: font_assets.pixel_font.clone(),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        ));
+    });
+    button
+}
+
+/// Hovering a button (with a mouse) selects it just like the arrow keys do, and
+/// clicking or tapping it presses it like `UiAction::Select`. Then highlights
+/// whichever button is selected.
 pub fn button_mouse_select(
+    interactions: Query<(&Interaction, &BtnGridPos), Changed<Interaction>>,
     mut element_button_query: Query<(&BtnGridPos, &mut BackgroundColor), With<Button>>,
-    state: ResMut<AbilityMenuState>,
+    mut state: ResMut<AbilityMenuState>,
+    mut submissions: EventWriter<Submission>,
 ) {
+    for (interaction, grid_pos) in &interactions {
+        match interaction {
+            Interaction::Hovered => {
+                state.selected_pos.row = grid_pos.row;
+                state.selected_pos.column = grid_pos.column;
+            }
+            Interaction::Clicked => {
+                state.selected_pos.row = grid_pos.row;
+                state.selected_pos.column = grid_pos.column;
+                if let Some(answer) = state.activate(grid_pos.option) {
+                    submissions.send(Submission::Answered(answer));
+                }
+            }
+            Interaction::None => {}
+        }
+    }
+
     for (grid_pos, mut color) in &mut element_button_query {
-        if state.selected_pos.row == grid_pos.row {
+        if state.selected_pos.same_slot(grid_pos) {
             // info!("button selected {}", grid_pos.row);
             // *color = BackgroundColor(Color::rgb(0.25, 0.55, 0.25).into());
             *color = BackgroundColor(Color::BLUE)
@@ -371,79 +755,280 @@ pub fn button_mouse_select(
     }
 }
 
+/// Clicking or tapping a [`NumericStepButton`] moves the dial like the arrow keys
+pub fn numeric_step_buttons(
+    interactions: Query<(&Interaction, &NumericStepButton), Changed<Interaction>>,
+    mut state: ResMut<AbilityMenuState>,
+) {
+    for (interaction, NumericStepButton(steps)) in &interactions {
+        if *interaction == Interaction::Clicked {
+            state.adjust_number(*steps);
+        }
+    }
+}
+
+/// Marks picked options: their position for ordering questions, a tick for select-all
+/// questions. Also keeps the numeric dial in sync with the current guess.
+pub fn update_answer_labels(
+    state: Res<AbilityMenuState>,
+    buttons: Query<(&BtnGridPos, &Children), Without<SubmitButton>>,
+    numeric_text: Query<Entity, With<NumericAnswerText>>,
+    mut texts: Query<&mut Text>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+
+    for (grid_pos, children) in &buttons {
+        if grid_pos
+            .option
+            .is_some_and(|option| state.eliminated.contains(&option))
+        {
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(*child) {
+                    text.sections[0].value.clear();
+                }
+            }
+            continue;
+        }
+        let picked = grid_pos
+            .option
+            .and_then(|option| state.picks.iter().position(|pick| *pick == option));
+        let label = match (&state.kind, picked) {
+            (QuestionKind::Ordering, Some(index)) => format!("{}. {}", index + 1, grid_pos.choice),
+            (QuestionKind::SelectAll { .. }, Some(_)) => format!("[X] {}", grid_pos.choice),
+            (QuestionKind::SelectAll { .. }, None) => format!("[ ] {}", grid_pos.choice),
+            _ => grid_pos.choice.clone(),
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+
+    if let QuestionKind::Numeric { step, .. } = state.kind {
+        for entity in &numeric_text {
+            if let Ok(mut text) = texts.get_mut(entity) {
+                text.sections[0].value = format!("< {} >", format_number(state.number, step));
+            }
+        }
+    }
+}
+
 pub fn button_keyboard_select(
-    element_button_query: Query<(&BtnGridPos, &SelectedQuestion)>,
+    element_button_query: Query<&BtnGridPos>,
     mut state: ResMut<AbilityMenuState>,
     mut query: Query<&ActionState<UiAction>>,
-    mut questions: ResMut<Assets<Question>>,
-    mut score: ResMut<Score>,
-    mut level_state: ResMut<NextState<LevelState>>,
-    mut game_state: ResMut<NextState<GameState>>,
+    mut submissions: EventWriter<Submission>,
 ) {
     for action_state in &mut query {
-        if action_state.just_pressed(UiAction::Down) {
-            state.selected_pos.row += 1;
-            if state.selected_pos.row >= 3 {
-                state.selected_pos.row = 0;
+        let kind = state.kind.clone();
+        if let QuestionKind::Numeric { .. } = kind {
+            if action_state.just_pressed(UiAction::Up) {
+                state.adjust_number(1.);
             }
-        }
-        if action_state.just_pressed(UiAction::Up) {
-            if state.selected_pos.row == 0 {
-                state.selected_pos.row = 0;
-            } else {
-                state.selected_pos.row -= 1;
+            if action_state.just_pressed(UiAction::Down) {
+                state.adjust_number(-1.);
+            }
+            if action_state.just_pressed(UiAction::Right) {
+                state.adjust_number(10.);
+            }
+            if action_state.just_pressed(UiAction::Left) {
+                state.adjust_number(-10.);
+            }
+        } else {
+            if action_state.just_pressed(UiAction::Down) {
+                state.move_down();
+            }
+            if action_state.just_pressed(UiAction::Up) {
+                state.move_up();
+            }
+            if action_state.just_pressed(UiAction::Left) {
+                state.move_sideways(-1);
+            }
+            if action_state.just_pressed(UiAction::Right) {
+                state.move_sideways(1);
             }
         }
-        if action_state.just_pressed(UiAction::Select) {
-            for (grid_pos, selected_question) in element_button_query.iter() {
-                if grid_pos.row == state.selected_pos.row {
-                    // info!("key code select: {}", grid_pos.choice);
-                    if let Some(handle) = questions.get_mut(&selected_question.question) {
-                        handle.used = true;
-                        if handle.answer == grid_pos.choice {
-                            info!("CORRECT ANSWER: {}", grid_pos.choice);
-                            score.0 += 1.;
-                            if score.0 >= 5. {
-                                game_state.set(GameState::WinScreen);
-                                level_state.set(LevelState::OverWorld);
-                            } else {
-                                level_state.set(LevelState::OverWorld);
-                            }
-                        } else {
-                            info!("WRONG!: {}", grid_pos.choice);
-                            level_state.set(LevelState::Wrong);
-                        }
+
+        if !action_state.just_pressed(UiAction::Select) {
+            continue;
+        }
+
+        let selected = element_button_query
+            .iter()
+            .find(|grid_pos| grid_pos.same_slot(&state.selected_pos))
+            .map(|grid_pos| grid_pos.option);
+        let answer = match (kind, selected) {
+            (QuestionKind::Numeric { .. }, _) => state.activate(None),
+            (_, Some(option)) => state.activate(option),
+            (_, None) => None,
+        };
+        if let Some(answer) = answer {
+            submissions.send(Submission::Answered(answer));
+            return;
+        }
+    }
+}
+
+/// Spends a lifeline on the current question when its button is pressed. Lifelines
+/// that would have no effect, like 50/50 on a true or false question, are kept.
+#[allow(clippy::too_many_arguments)]
+pub fn use_lifeline(
+    query: Query<&ActionState<UiAction>>,
+    mut lifelines: ResMut<Lifelines>,
+    mut state: ResMut<AbilityMenuState>,
+    mut questions: ResMut<Assets<Question>>,
+    mut rng: ResMut<SessionRng>,
+    mut time_since_level_start: ResMut<TimeSinceLevelStart>,
+    config: Res<ConsoleConfig>,
+    mut level_state: ResMut<NextState<LevelState>>,
+) {
+    for action_state in &query {
+        for lifeline in Lifeline::ALL {
+            if !action_state.just_pressed(lifeline.action()) || lifelines.count(lifeline) == 0 {
+                continue;
+            }
+            let Some(question) = questions.get_mut(&state.question) else {
+                continue;
+            };
+
+            match lifeline {
+                Lifeline::FiftyFifty => {
+                    let eliminated = fifty_fifty(question, &state.eliminated, &mut rng);
+                    if eliminated.is_empty() {
+                        continue;
                     }
-                    return;
+                    state.eliminated.extend(eliminated);
+                }
+                Lifeline::Skip => {
+                    // Re-entering the console swaps in a fresh question
+                    question.used = true;
+                    level_state.set(LevelState::Console);
+                }
+                Lifeline::ExtraTime => {
+                    time_since_level_start.0 =
+                        (time_since_level_start.0 - config.extra_time).max(0.);
                 }
             }
+            lifelines.take(lifeline);
+            info!("Used lifeline {:?}", lifeline);
         }
     }
 }
 
-pub fn destroy_console_state_entities(
+/// Picks half of the wrong options still on screen, always leaving at least one
+fn fifty_fifty(question: &Question, eliminated: &[usize], rng: &mut SessionRng) -> Vec<usize> {
+    let Some(answer) = question.answer_index() else {
+        return vec![];
+    };
+    if !matches!(
+        question.kind,
+        QuestionKind::SingleChoice | QuestionKind::TrueFalse
+    ) {
+        return vec![];
+    }
+
+    let mut wrong: Vec<usize> = (0..question.options.len())
+        .filter(|option| *option != answer && !eliminated.contains(option))
+        .collect();
+    let count = wrong.len().div_ceil(2).min(wrong.len().saturating_sub(1));
+    wrong.shuffle(&mut **rng);
+    wrong.truncate(count);
+    wrong
+}
+
+/// Counts down the time left to answer and gives up on the question once it runs out
+pub fn answer_countdown(
+    time: Res<Time>,
+    timer: Option<ResMut<AnswerTimer>>,
+    mut timer_texts: Query<&mut Text, With<AnswerTimerText>>,
+    mut submissions: EventWriter<Submission>,
+) {
+    let Some(mut timer) = timer else {
+        return;
+    };
+    timer.tick(time.delta());
+
+    let seconds_left = timer.remaining_secs();
+    for mut text in &mut timer_texts {
+        text.sections[0].value = format!("TIME {:.0}", seconds_left.ceil());
+        if seconds_left <= ANSWER_TIMER_WARNING {
+            text.sections[0].style.color = Color::RED;
+        }
+    }
+
+    if timer.just_finished() {
+        submissions.send(Submission::TimedOut);
+    }
+}
+
+/// Grades the first submission of the frame, scores it and moves on to the reveal
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_submission(
     mut commands: Commands,
-    entities_query: Query<Entity, With<UiRootNode>>,
-    mut keyboard: ResMut<Input<KeyCode>>,
+    mut submissions: EventReader<Submission>,
+    state: Res<AbilityMenuState>,
+    timer: Option<Res<AnswerTimer>>,
+    config: Res<ConsoleConfig>,
+    mut questions: ResMut<Assets<Question>>,
+    mut score: ResMut<Score>,
+    rules: Res<GameRules>,
+    mut time_since_level_start: ResMut<TimeSinceLevelStart>,
+    mut lives: Query<&mut Lives>,
+    mut level_state: ResMut<NextState<LevelState>>,
+    time: Res<Time>,
 ) {
-    info!("[ConsolePlugin] Destroying state entities before exiting...");
-    commands.remove_resource::<AbilityMenuState>();
-    for entity in entities_query.iter() {
-        commands.entity(entity).despawn_recursive();
+    // Anything sent after the first submission arrived too late to count
+    let Some(submission) = submissions.iter().next().cloned() else {
+        return;
+    };
+    submissions.clear();
+
+    let Some(question) = questions.get_mut(&state.question) else {
+        return;
+    };
+    question.used = true;
+    let correct = match &submission {
+        Submission::Answered(answer) => grade(question, answer),
+        Submission::TimedOut => false,
+    };
+
+    if correct {
+        info!("CORRECT ANSWER: {:?}", submission);
+        score.correct += 1.;
+        if let Some(timer) = timer {
+            score.speed_bonus += config.speed_bonus * timer.percent_left();
+        }
+    } else {
+        info!("WRONG!: {:?}", submission);
+        time_since_level_start.0 += rules.wrong_answer_penalty;
+        for mut lives in &mut lives {
+            lives.lose(Lives::WRONG_ANSWER_DAMAGE);
+        }
     }
-    keyboard.clear();
-    info!("[ConsolePlugin] Exiting console state")
+
+    commands.insert_resource(Reveal {
+        question: state.question.clone(),
+        submission,
+        correct,
+        time_taken: time.elapsed_seconds() - state.asked_at,
+    });
+    level_state.set(LevelState::Reveal);
 }
 
-pub fn destroy_wrong_state_entities(
+pub fn destroy_console_state_entities(
     mut commands: Commands,
-    entities_query: Query<Entity, With<WrongRootNode>>,
+    entities_query: Query<Entity, With<UiRootNode>>,
     mut keyboard: ResMut<Input<KeyCode>>,
 ) {
-    info!("[ConsolePlugin] Destroying wrong state entities before exiting...");
+    info!("[ConsolePlugin] Destroying state entities before exiting...");
+    commands.remove_resource::<AbilityMenuState>();
+    commands.remove_resource::<AnswerTimer>();
     for entity in entities_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
     keyboard.clear();
-    info!("[ConsolePlugin] Exiting wrong console state")
+    info!("[ConsolePlugin] Exiting console state")
 }
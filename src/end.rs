@@ -3,8 +3,13 @@ use bevy::reflect::Reflect;
 use leafwing_input_manager::prelude::ActionState;
 
 use crate::actions::UiAction;
+use crate::clock::{LevelClock, TimeSinceLevelStart};
+use crate::console::{AskedQuestions, LoggedAnswer, CORRECT_COLOR, WRONG_COLOR};
 use crate::despawn::despawn_entity;
+use crate::leaderboard::{self, HighScore, InitialsEntry, Leaderboard, LeaderboardPanel};
 use crate::loading::{FontAssets, Question};
+use crate::prizes::{self, ClaimCode, EventSecret};
+use crate::random::SessionRng;
 use crate::ui::Score;
 use crate::{GameState, LevelState};
 
@@ -16,30 +21,83 @@ impl Plugin for EndPlugin {
             .add_system(on_win.in_schedule(OnEnter(GameState::WinScreen)))
             .add_system(reset_state.in_set(OnUpdate(GameState::WinScreen)))
             .add_system(reset_state.in_set(OnUpdate(GameState::GameOver)))
+            .add_system(clear_session.in_schedule(OnEnter(GameState::Controls)))
+            .add_system(scroll_summary.in_set(OnUpdate(GameState::WinScreen)))
+            .add_system(scroll_summary.in_set(OnUpdate(GameState::GameOver)))
             .add_system(despawn_entity::<EndScreen>.in_schedule(OnExit(GameState::WinScreen)))
             .add_system(despawn_entity::<EndScreen>.in_schedule(OnExit(GameState::GameOver)));
     }
 }
 
+/// Answers visible at once in the run summary
+const SUMMARY_ROWS: usize = 6;
+
 // ------ SYSTEMS ------
 
 fn reset_state(
     query: Query<&ActionState<UiAction>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut game_phase: ResMut<NextState<LevelState>>,
-    mut score: ResMut<Score>,
-    mut questions: ResMut<Assets<Question>>,
+    initials_entry: Option<Res<InitialsEntry>>,
 ) {
+    // Start and select share a key, so wait until the initials are in
+    if initials_entry.is_some() {
+        return;
+    }
     for action_state in &query {
         if action_state.just_pressed(UiAction::Start) {
-            *score = Score::default();
-            questions.iter_mut().for_each(|i| i.1.used = false);
             game_phase.set(LevelState::None);
             game_state.set(GameState::Controls);
         }
     }
 }
 
+/// Forgets the previous session, whether it ended on the end screen or a kiosk
+/// went back to the menu halfway through
+fn clear_session(
+    mut commands: Commands,
+    mut questions: ResMut<Assets<Question>>,
+    mut asked: ResMut<AskedQuestions>,
+) {
+    commands.insert_resource(Score::default());
+    questions.iter_mut().for_each(|i| i.1.used = false);
+    asked.0.clear();
+}
+
+/// Scrolls the run summary with up and down once any initials are entered
+fn scroll_summary(
+    mut commands: Commands,
+    query: Query<&ActionState<UiAction>>,
+    mut summaries: Query<(Entity, &mut RunSummary)>,
+    asked: Res<AskedQuestions>,
+    asset_holder: Res<FontAssets>,
+    initials_entry: Option<Res<InitialsEntry>>,
+) {
+    if initials_entry.is_some() {
+        return;
+    }
+    let last_offset = asked.answered().count().saturating_sub(SUMMARY_ROWS);
+    for action_state in &query {
+        for (entity, mut summary) in &mut summaries {
+            let offset = if action_state.just_pressed(UiAction::Up) {
+                summary.offset.saturating_sub(1)
+            } else if action_state.just_pressed(UiAction::Down) {
+                (summary.offset + 1).min(last_offset)
+            } else {
+                continue;
+            };
+            if offset != summary.offset {
+                summary.offset = offset;
+                let mut summary = commands.entity(entity);
+                summary.despawn_descendants();
+                summary.with_children(|parent| {
+                    spawn_summary_rows(parent, &asset_holder, &asked, offset)
+                });
+            }
+        }
+    }
+}
+
 // ------ COMPONENTS ------
 
 #[derive(Reflect, Component, Default)]
@@ -50,29 +108,105 @@ pub struct EndScreen;
 #[reflect(Component)]
 pub struct ResetButton;
 
+/// Every question of the run, scrolled to `offset`
+#[derive(Component, Default)]
+pub struct RunSummary {
+    offset: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn on_lose(
     mut commands: Commands,
     existing_end_screens: Query<Entity, With<EndScreen>>,
     asset_holder: Res<FontAssets>,
+    rng: Res<SessionRng>,
+    score: Res<Score>,
+    level_clock: Res<LevelClock>,
+    time_since_level_start: Res<TimeSinceLevelStart>,
+    leaderboard: Res<Leaderboard>,
+    asked: Res<AskedQuestions>,
 ) {
     for entity in existing_end_screens.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    build_end_screen(&mut commands, &asset_holder, false);
+    let entry = initials_entry(&score, &level_clock, &time_since_level_start, &leaderboard);
+    build_end_screen(
+        &mut commands,
+        &asset_holder,
+        false,
+        None,
+        rng.seed(),
+        &leaderboard,
+        &asked,
+        entry.as_ref(),
+    );
+    if let Some(entry) = entry {
+        commands.insert_resource(entry);
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn on_win(
     mut commands: Commands,
     existing_end_screens: Query<Entity, With<EndScreen>>,
     asset_holder: Res<FontAssets>,
+    rng: Res<SessionRng>,
+    score: Res<Score>,
+    level_clock: Res<LevelClock>,
+    time_since_level_start: Res<TimeSinceLevelStart>,
+    leaderboard: Res<Leaderboard>,
+    asked: Res<AskedQuestions>,
+    secret: Res<EventSecret>,
 ) {
     for entity in existing_end_screens.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    build_end_screen(&mut commands, &asset_holder, true);
+    let entry = initials_entry(&score, &level_clock, &time_since_level_start, &leaderboard);
+    let claim_code =
+        prizes::issue_claim_code(&secret, score.correct + score.speed_bonus, rng.seed());
+    build_end_screen(
+        &mut commands,
+        &asset_holder,
+        true,
+        claim_code.as_ref(),
+        rng.seed(),
+        &leaderboard,
+        &asked,
+        entry.as_ref(),
+    );
+    if let Some(entry) = entry {
+        commands.insert_resource(entry);
+    }
+}
+
+/// Asks for initials if the run made the high score table
+fn initials_entry(
+    score: &Score,
+    level_clock: &LevelClock,
+    time_since_level_start: &TimeSinceLevelStart,
+    leaderboard: &Leaderboard,
+) -> Option<InitialsEntry> {
+    let high_score = HighScore {
+        initials: String::new(),
+        score: score.correct + score.speed_bonus,
+        time_left: level_clock.time_left(time_since_level_start.0),
+    };
+    leaderboard
+        .qualifies(&high_score)
+        .then(|| InitialsEntry::new(high_score))
 }
 
-fn build_end_screen(commands: &mut Commands, asset_holder: &Res<FontAssets>, win: bool) {
+#[allow(clippy::too_many_arguments)]
+fn build_end_screen(
+    commands: &mut Commands,
+    asset_holder: &Res<FontAssets>,
+    win: bool,
+    claim_code: Option<&ClaimCode>,
+    seed: u64,
+    leaderboard: &Leaderboard,
+    asked: &AskedQuestions,
+    initials_entry: Option<&InitialsEntry>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -87,7 +221,7 @@ fn build_end_screen(commands: &mut Commands, asset_holder: &Res<FontAssets>, win
                     },
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
-                    gap: Size::height(Val::Px(20.)),
+                    gap: Size::height(Val::Px(12.)),
                     size: Size {
                         width: Val::Percent(100.),
                         height: Val::Percent(100.),
@@ -115,13 +249,24 @@ fn build_end_screen(commands: &mut Commands, asset_holder: &Res<FontAssets>, win
                 )
                 .with_text_alignment(TextAlignment::Center),
             );
+            // Volunteers check the code with `redeem-prize` before handing out a tag
+            if let Some(claim_code) = claim_code {
+                parent.spawn(TextBundle::from_section(
+                    format!("CLAIM CODE: {}", claim_code),
+                    TextStyle {
+                        font: asset_holder.crt_font.clone(),
+                        font_size: 48.,
+                        color: Color::YELLOW,
+                    },
+                ));
+            }
             if win {
                 parent.spawn(
                     TextBundle::from_section(
                         "(Limit of 1 path tag per family please)",
                         TextStyle {
                             font: asset_holder.crt_font.clone(),
-                            font_size: 48.,
+                            font_size: 32.,
                             color: Color::PINK,
                         },
                     )
@@ -168,6 +313,72 @@ fn build_end_screen(commands: &mut Commands, asset_holder: &Res<FontAssets>, win
                     ));
                 });
 
+            // High scores next to the run summary
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::FlexStart,
+                        gap: Size::width(Val::Px(80.)),
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    gap: Size::height(Val::Px(4.)),
+                                    ..default()
+                                },
+                                background_color: Color::NONE.into(),
+                                ..default()
+                            },
+                            LeaderboardPanel,
+                        ))
+                        .with_children(|parent| match initials_entry {
+                            Some(entry) => {
+                                leaderboard::spawn_initials_entry(parent, asset_holder, entry)
+                            }
+                            None => leaderboard::spawn_table(
+                                parent,
+                                asset_holder,
+                                leaderboard,
+                                leaderboard::END_SCREEN_ROWS,
+                                None,
+                            ),
+                        });
+
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::FlexStart,
+                                    gap: Size::height(Val::Px(4.)),
+                                    ..default()
+                                },
+                                background_color: Color::NONE.into(),
+                                ..default()
+                            },
+                            RunSummary::default(),
+                        ))
+                        .with_children(|parent| spawn_summary_rows(parent, asset_holder, asked, 0));
+                });
+
+            // Lets volunteers report the run so it can be replayed with `--seed`
+            parent.spawn(TextBundle::from_section(
+                format!("SEED {}", seed),
+                TextStyle {
+                    font: asset_holder.crt_font.clone(),
+                    font_size: 24.,
+                    color: Color::GRAY,
+                },
+            ));
+
             // parent
             //     .spawn((
             //         ButtonBundle {
@@ -195,3 +406,62 @@ fn build_end_screen(commands: &mut Commands, asset_holder: &Res<FontAssets>, win
             //     });
         });
 }
+
+/// The answers from `offset` on that fit in the run summary, with a hint when
+/// there are more above or below
+fn spawn_summary_rows(
+    parent: &mut ChildBuilder,
+    asset_holder: &FontAssets,
+    asked: &AskedQuestions,
+    offset: usize,
+) {
+    let answers: Vec<&LoggedAnswer> = asked.answered().collect();
+    let text_style = |color: Color| TextStyle {
+        font: asset_holder.crt_font.clone(),
+        font_size: 24.,
+        color,
+    };
+    parent.spawn(TextBundle::from_section(
+        "YOUR ANSWERS",
+        TextStyle {
+            font: asset_holder.crt_font.clone(),
+            font_size: 36.,
+            color: Color::WHITE,
+        },
+    ));
+    if answers.is_empty() {
+        parent.spawn(TextBundle::from_section(
+            "NO QUESTIONS ANSWERED",
+            text_style(Color::GRAY),
+        ));
+        return;
+    }
+
+    let more_above = offset > 0;
+    let more_below = offset + SUMMARY_ROWS < answers.len();
+    parent.spawn(TextBundle::from_section(
+        if more_above { "^ MORE" } else { "" },
+        text_style(Color::GRAY),
+    ));
+    for (number, answer) in answers.iter().enumerate().skip(offset).take(SUMMARY_ROWS) {
+        let color = if answer.correct {
+            CORRECT_COLOR
+        } else {
+            WRONG_COLOR
+        };
+        parent.spawn(TextBundle::from_section(
+            format!(
+                "{:>2}. {}: {} ({:.1}s)",
+                number + 1,
+                answer.key,
+                answer.chosen,
+                answer.time_taken
+            ),
+            text_style(color),
+        ));
+    }
+    parent.spawn(TextBundle::from_section(
+        if more_below { "v MORE" } else { "" },
+        text_style(Color::GRAY),
+    ));
+}
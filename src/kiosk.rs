@@ -0,0 +1,139 @@
+use crate::{GameState, LevelState};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::touch::TouchInput;
+use bevy::prelude::*;
+use bevy::window::WindowCloseRequested;
+use std::env;
+
+/// Environment variable that can be used instead of the `--kiosk` argument
+const KIOSK_VARIABLE: &str = "FLAMINGO_KIOSK";
+
+/// Environment variable that can be used instead of the `--kiosk-idle` argument
+const IDLE_VARIABLE: &str = "FLAMINGO_KIOSK_IDLE";
+
+const DEFAULT_IDLE_SECONDS: f32 = 90.;
+
+pub struct KioskPlugin;
+
+/// Lets the game run unattended at events: after [`Kiosk::idle_seconds`] without
+/// input it goes back to the main menu, and the window can not be closed.
+///
+/// The window is closed here rather than by bevy, so `WindowPlugin` must be
+/// added with `close_when_requested: false`.
+impl Plugin for KioskPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Kiosk::from_args())
+            .init_resource::<IdleTime>()
+            .add_system(track_idle_time)
+            .add_system(reset_when_idle.after(track_idle_time))
+            .add_system(close_when_requested);
+    }
+}
+
+/// Kiosk settings, from `--kiosk` and `--kiosk-idle <seconds>` or the
+/// `FLAMINGO_KIOSK` and `FLAMINGO_KIOSK_IDLE` environment variables
+#[derive(Resource, Clone, Debug)]
+pub struct Kiosk {
+    pub enabled: bool,
+    /// Seconds without input before the game returns to the main menu
+    pub idle_seconds: f32,
+}
+
+impl Kiosk {
+    fn from_args() -> Self {
+        let mut enabled =
+            env::var(KIOSK_VARIABLE).is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"));
+        let mut idle = env::var(IDLE_VARIABLE).ok();
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--kiosk" {
+                enabled = true;
+            } else if arg == "--kiosk-idle" {
+                idle = args.next();
+            } else if let Some(seconds) = arg.strip_prefix("--kiosk-idle=") {
+                idle = Some(seconds.to_string());
+            }
+        }
+
+        let idle_seconds = match idle.map(|value| (value.trim().parse::<f32>(), value)) {
+            None => DEFAULT_IDLE_SECONDS,
+            Some((Ok(seconds), _)) if seconds > 0. => seconds,
+            Some((_, value)) => {
+                warn!(
+                    "Ignoring kiosk idle time {:?}, it is not a positive number",
+                    value
+                );
+                DEFAULT_IDLE_SECONDS
+            }
+        };
+        if enabled {
+            info!(
+                "Kiosk mode, returning to the menu after {}s idle",
+                idle_seconds
+            );
+        }
+        Kiosk {
+            enabled,
+            idle_seconds,
+        }
+    }
+}
+
+/// Seconds since the last key press, click, touch or gamepad button
+#[derive(Resource, Default, Debug)]
+pub struct IdleTime(pub f32);
+
+fn track_idle_time(
+    time: Res<Time>,
+    mut idle: ResMut<IdleTime>,
+    mut keys: EventReader<KeyboardInput>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut touches: EventReader<TouchInput>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    let input = keys.iter().count()
+        + mouse_buttons.iter().count()
+        + touches.iter().count()
+        + gamepad_buttons.get_just_pressed().count();
+    if input > 0 {
+        idle.0 = 0.;
+    } else {
+        idle.0 += time.delta_seconds();
+    }
+}
+
+fn reset_when_idle(
+    kiosk: Res<Kiosk>,
+    mut idle: ResMut<IdleTime>,
+    state: Res<State<GameState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut level_state: ResMut<NextState<LevelState>>,
+) {
+    if !kiosk.enabled || idle.0 < kiosk.idle_seconds {
+        return;
+    }
+    if matches!(state.0, GameState::Loading | GameState::Menu) {
+        return;
+    }
+    info!("No input for {}s, back to the menu", kiosk.idle_seconds);
+    idle.0 = 0.;
+    level_state.set(LevelState::None);
+    game_state.set(GameState::Menu);
+}
+
+/// Closes windows on request, unless this is a kiosk
+fn close_when_requested(
+    mut commands: Commands,
+    kiosk: Res<Kiosk>,
+    mut requests: EventReader<WindowCloseRequested>,
+) {
+    for request in requests.iter() {
+        if kiosk.enabled {
+            info!("Ignoring request to close the window in kiosk mode");
+        } else {
+            commands.entity(request.window).despawn();
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use crate::actions::UiAction;
+use crate::loading::FontAssets;
+use crate::storage;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+use leafwing_input_manager::prelude::ActionState;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+
+/// Entries kept on disk and shown in the menu's attract loop
+pub const MAX_ENTRIES: usize = 10;
+
+/// Entries shown on the end screen, which also has the result to fit
+pub const END_SCREEN_ROWS: usize = 5;
+
+const INITIALS: usize = 3;
+const LETTERS: &[u8; 26] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+const HIGHLIGHT_COLOR: Color = Color::rgb(1.0, 0.925, 0.153);
+
+pub struct LeaderboardPlugin;
+
+/// Keeps the high scores in `highscores.ron` in the user's data directory.
+/// New entries are made on the end screen, see `end::build_end_screen`.
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Leaderboard::load())
+            .add_system(enter_initials.in_set(OnUpdate(GameState::WinScreen)))
+            .add_system(enter_initials.in_set(OnUpdate(GameState::GameOver)))
+            .add_system(remove_initials_entry.in_schedule(OnExit(GameState::WinScreen)))
+            .add_system(remove_initials_entry.in_schedule(OnExit(GameState::GameOver)));
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub initials: String,
+    /// Correct answers plus speed bonus
+    pub score: f32,
+    /// Seconds that were left on the clock
+    pub time_left: f32,
+}
+
+impl HighScore {
+    /// Higher scores first, ties go to whoever had more time left
+    fn rank(&self, other: &HighScore) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then(other.time_left.total_cmp(&self.time_left))
+    }
+}
+
+/// The best runs, best first
+#[derive(serde::Serialize, serde::Deserialize, Resource, Default, Debug)]
+pub struct Leaderboard {
+    pub entries: Vec<HighScore>,
+}
+
+impl Leaderboard {
+    fn path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("highscores.ron"))
+    }
+
+    /// An unreadable file is logged and treated as empty, so a broken table
+    /// never keeps the game from starting
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Leaderboard::default();
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Leaderboard::default();
+        };
+        match ron::from_str(&text) {
+            Ok(leaderboard) => leaderboard,
+            Err(error) => {
+                warn!("Ignoring high scores in {}: {}", path.display(), error);
+                Leaderboard::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            warn!("No data directory to save high scores in");
+            return;
+        };
+        let result = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|error| error.to_string())
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+                }
+                fs::write(&path, text).map_err(|error| error.to_string())
+            });
+        if let Err(error) = result {
+            error!(
+                "Could not save high scores to {}: {}",
+                path.display(),
+                error
+            );
+        }
+    }
+
+    /// Removes every entry, on disk too
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.save();
+        info!("Cleared the high scores");
+    }
+
+    /// Whether `high_score` would make it onto the table
+    pub fn qualifies(&self, high_score: &HighScore) -> bool {
+        high_score.score > 0.
+            && (self.entries.len() < MAX_ENTRIES
+                || self
+                    .entries
+                    .last()
+                    .is_some_and(|last| high_score.rank(last) == Ordering::Less))
+    }
+
+    /// Adds `high_score` in rank order, returning its place if it was kept
+    pub fn insert(&mut self, high_score: HighScore) -> Option<usize> {
+        let place = self
+            .entries
+            .iter()
+            .position(|entry| high_score.rank(entry) == Ordering::Less)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(place, high_score);
+        self.entries.truncate(MAX_ENTRIES);
+        (place < MAX_ENTRIES).then_some(place)
+    }
+}
+
+/// Arcade style initials entry for a run that made the table: up and down pick
+/// a letter, left and right move between letters and select confirms.
+#[derive(Resource, Debug)]
+pub struct InitialsEntry {
+    high_score: HighScore,
+    letters: [usize; INITIALS],
+    cursor: usize,
+}
+
+impl InitialsEntry {
+    pub fn new(high_score: HighScore) -> Self {
+        InitialsEntry {
+            high_score,
+            letters: [0; INITIALS],
+            cursor: 0,
+        }
+    }
+
+    fn letter(&self, index: usize) -> char {
+        LETTERS[self.letters[index]] as char
+    }
+
+    /// Moves the letter under the cursor `steps` places on, wrapping from Z to A
+    fn cycle_letter(&mut self, steps: usize) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = (*letter + steps) % LETTERS.len();
+    }
+
+    fn initials(&self) -> String {
+        (0..INITIALS).map(|index| self.letter(index)).collect()
+    }
+}
+
+/// Holds either the initials entry or the high score table on the end screen
+#[derive(Component)]
+pub struct LeaderboardPanel;
+
+#[derive(Component)]
+struct InitialLetter(usize);
+
+fn text_style(fonts: &FontAssets, font_size: f32, color: Color) -> TextStyle {
+    TextStyle {
+        font: fonts.crt_font.clone(),
+        font_size,
+        color,
+    }
+}
+
+pub fn spawn_initials_entry(parent: &mut ChildBuilder, fonts: &FontAssets, entry: &InitialsEntry) {
+    parent.spawn(TextBundle::from_section(
+        "NEW HIGH SCORE! ENTER YOUR INITIALS",
+        text_style(fonts, 36., HIGHLIGHT_COLOR),
+    ));
+    parent.spawn(TextBundle::from_section(
+        format!("{:.1} POINTS", entry.high_score.score),
+        text_style(fonts, 24., Color::WHITE),
+    ));
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                gap: Size::width(Val::Px(24.)),
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            for index in 0..INITIALS {
+                let color = if index == entry.cursor {
+                    HIGHLIGHT_COLOR
+                } else {
+                    Color::WHITE
+                };
+                parent.spawn((
+                    TextBundle::from_section(
+                        entry.letter(index).to_string(),
+                        text_style(fonts, 72., color),
+                    ),
+                    InitialLetter(index),
+                ));
+            }
+        });
+}
+
+/// The first `rows` entries of the high score table, plus the one at
+/// `highlight` if it is further down
+pub fn spawn_table(
+    parent: &mut ChildBuilder,
+    fonts: &FontAssets,
+    leaderboard: &Leaderboard,
+    rows: usize,
+    highlight: Option<usize>,
+) {
+    parent.spawn(TextBundle::from_section(
+        "HIGH SCORES",
+        text_style(fonts, 36., Color::WHITE),
+    ));
+    if leaderboard.entries.is_empty() {
+        parent.spawn(TextBundle::from_section(
+            "NO SCORES YET",
+            text_style(fonts, 24., Color::GRAY),
+        ));
+    }
+    let shown = leaderboard
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(place, _)| *place < rows || Some(*place) == highlight);
+    for (place, entry) in shown {
+        let color = if Some(place) == highlight {
+            HIGHLIGHT_COLOR
+        } else {
+            Color::WHITE
+        };
+        let time_left = entry.time_left.max(0.) as u32;
+        parent.spawn(TextBundle::from_section(
+            format!(
+                "{:>2}. {:<3} {:>5.1} {:0>2}:{:0>2}",
+                place + 1,
+                entry.initials,
+                entry.score,
+                time_left / 60,
+                time_left % 60
+            ),
+            text_style(fonts, 24., color),
+        ));
+    }
+}
+
+fn enter_initials(
+    mut commands: Commands,
+    entry: Option<ResMut<InitialsEntry>>,
+    actions: Query<&ActionState<UiAction>>,
+    mut letters: Query<(&mut Text, &InitialLetter)>,
+    panel: Query<Entity, With<LeaderboardPanel>>,
+    mut leaderboard: ResMut<Leaderboard>,
+    fonts: Res<FontAssets>,
+) {
+    let Some(mut entry) = entry else {
+        return;
+    };
+    let mut done = false;
+    for action_state in &actions {
+        if action_state.just_pressed(UiAction::Up) {
+            entry.cycle_letter(1);
+        } else if action_state.just_pressed(UiAction::Down) {
+            entry.cycle_letter(LETTERS.len() - 1);
+        } else if action_state.just_pressed(UiAction::Left) {
+            entry.cursor = entry.cursor.saturating_sub(1);
+        } else if action_state.just_pressed(UiAction::Right) {
+            entry.cursor = (entry.cursor + 1).min(INITIALS - 1);
+        } else if action_state.just_pressed(UiAction::Select) {
+            if entry.cursor + 1 == INITIALS {
+                done = true;
+            } else {
+                entry.cursor += 1;
+            }
+        }
+    }
+
+    if done {
+        let mut high_score = entry.high_score.clone();
+        high_score.initials = entry.initials();
+        info!("New high score {:?}", high_score);
+        let place = leaderboard.insert(high_score);
+        leaderboard.save();
+        commands.remove_resource::<InitialsEntry>();
+        for panel in &panel {
+            let mut panel = commands.entity(panel);
+            panel.despawn_descendants();
+            panel.with_children(|parent| {
+                spawn_table(parent, &fonts, &leaderboard, END_SCREEN_ROWS, place)
+            });
+        }
+    } else if entry.is_changed() {
+        for (mut text, InitialLetter(index)) in &mut letters {
+            text.sections[0].value = entry.letter(*index).to_string();
+            text.sections[0].style.color = if *index == entry.cursor {
+                HIGHLIGHT_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+fn remove_initials_entry(mut commands: Commands) {
+    commands.remove_resource::<InitialsEntry>();
+}
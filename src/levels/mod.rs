@@ -2,13 +2,17 @@ mod components;
 mod systems;
 
 use crate::levels::components::WallBundle;
-use crate::levels::systems::{camera_fit_inside_current_level, spawn_wall_collision};
+use crate::levels::systems::{
+    apply_level_clock, camera_fit_inside_current_level, spawn_wall_collision,
+};
 use crate::loading::LevelAssets;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{prelude::LdtkIntCellAppExt, *};
 use bevy_rapier2d::plugin::PhysicsSet;
 
+pub use systems::clock_for_level;
+
 pub struct LevelsPlugin {
     pub level_index: usize,
 }
@@ -27,6 +31,7 @@ impl Plugin for LevelsPlugin {
             .add_system(spawn_level.in_schedule(OnEnter(GameState::Playing)))
             .add_system(camera_fit_inside_current_level)
             .add_system(spawn_wall_collision)
+            .add_system(apply_level_clock)
             .add_system(despawn_world.in_schedule(OnExit(GameState::Playing)));
     }
 }
@@ -3,6 +3,7 @@ use bevy_ecs_ldtk::prelude::*;
 
 use std::collections::{HashMap, HashSet};
 
+use crate::clock::LevelClock;
 use crate::levels::components::Wall;
 use crate::player::alt::PlayerAlt;
 use bevy_rapier2d::prelude::*;
@@ -237,3 +238,65 @@ pub fn spawn_wall_collision(
         });
     }
 }
+
+/// Applies the `time_limit` and `warning_at` custom fields of the selected level
+/// to the [`LevelClock`]. Either can be an integer or a float field; a level
+/// without them keeps the event's [`GameRules`](crate::rules::GameRules).
+pub fn apply_level_clock(
+    level_query: Query<&Handle<LdtkLevel>, Added<Handle<LdtkLevel>>>,
+    level_selection: Res<LevelSelection>,
+    ldtk_levels: Res<Assets<LdtkLevel>>,
+    mut level_clock: ResMut<LevelClock>,
+) {
+    for level_handle in &level_query {
+        let Some(ldtk_level) = ldtk_levels.get(level_handle) else {
+            continue;
+        };
+        let level = &ldtk_level.level;
+        if !level_selection.is_match(&0, level) {
+            continue;
+        }
+        *level_clock = clock_for_level(level, *level_clock);
+        info!(
+            "Level '{}' has {}s on the clock, warning at {}s",
+            level.identifier, level_clock.time_limit, level_clock.warning_at
+        );
+    }
+}
+
+/// `base` with whichever of the `time_limit` and `warning_at` custom fields
+/// `level` sets
+pub fn clock_for_level(level: &ldtk::Level, base: LevelClock) -> LevelClock {
+    LevelClock {
+        time_limit: level_field(level, "time_limit").unwrap_or(base.time_limit),
+        warning_at: level_field(level, "warning_at").unwrap_or(base.warning_at),
+    }
+}
+
+/// A positive number from the level's custom field `identifier`, if it is set
+fn level_field(level: &ldtk::Level, identifier: &str) -> Option<f32> {
+    let field = level
+        .field_instances
+        .iter()
+        .find(|field| field.identifier == identifier)?;
+    let value = match field.value {
+        FieldValue::Float(value) => value,
+        FieldValue::Int(value) => value.map(|value| value as f32),
+        _ => {
+            warn!(
+                "Ignoring field '{}' of level '{}', it is not a number",
+                identifier, level.identifier
+            );
+            None
+        }
+    }?;
+    if value > 0. {
+        Some(value)
+    } else {
+        warn!(
+            "Ignoring field '{}' of level '{}', it is not positive",
+            identifier, level.identifier
+        );
+        None
+    }
+}
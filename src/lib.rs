@@ -1,26 +1,47 @@
 mod actions;
+mod admin;
 mod animation;
+mod attract;
 mod audio;
-mod clock;
+pub mod clock;
 mod console;
 mod despawn;
 mod end;
+mod kiosk;
+mod leaderboard;
 mod levels;
+mod lifelines;
 mod loading;
 mod menu;
+mod pause;
 mod player;
+pub mod prizes;
+mod random;
+mod rules;
+mod storage;
+mod trivia;
 mod ui;
+pub mod validation;
 
 use crate::actions::ActionsPlugin;
+use crate::admin::AdminPlugin;
 use crate::audio::InternalAudioPlugin;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
+use crate::pause::PausePlugin;
 
 use crate::animation::SpriteSheetAnimationPlugin;
+use crate::attract::AttractPlugin;
 use crate::clock::ClockPlugin;
 use crate::console::ConsolePlugin;
+use crate::kiosk::KioskPlugin;
+use crate::leaderboard::LeaderboardPlugin;
 use crate::levels::LevelsPlugin;
-use crate::player::{alt::PlayerAltPlugin, AltGoalPlugin};
+use crate::lifelines::LifelinesPlugin;
+use crate::player::{alt::PlayerAltPlugin, rewind::RewindPlugin, AltGoalPlugin};
+use crate::prizes::PrizesPlugin;
+use crate::random::RandomPlugin;
+use crate::rules::RulesPlugin;
 use crate::ui::UiPlugin;
 use bevy::app::App;
 #[cfg(debug_assertions)]
@@ -51,7 +72,10 @@ pub enum LevelState {
     None,
     OverWorld,
     Console,
-    Wrong,
+    /// Shows the answer to the question just asked
+    Reveal,
+    /// The level is frozen behind the pause menu
+    Paused,
 }
 
 pub struct GamePlugin;
@@ -60,6 +84,8 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<GameState>()
             .add_state::<LevelState>()
+            .add_plugin(RandomPlugin)
+            .add_plugin(RulesPlugin)
             .add_plugin(InternalAudioPlugin)
             .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
             .insert_resource(RapierConfiguration {
@@ -72,6 +98,9 @@ impl Plugin for GamePlugin {
             // })
             .add_plugin(LoadingPlugin)
             .add_plugin(MenuPlugin)
+            .add_plugin(AttractPlugin)
+            .add_plugin(KioskPlugin)
+            .add_plugin(AdminPlugin)
             .add_plugin(LevelsPlugin { level_index: 0 })
             .add_plugin(ActionsPlugin)
             .add_plugin(SpriteSheetAnimationPlugin)
@@ -79,11 +108,16 @@ impl Plugin for GamePlugin {
             .add_plugin(UiPlugin)
             // .add_plugin(PlayerPlugin)
             .add_plugin(PlayerAltPlugin)
+            .add_plugin(RewindPlugin)
+            .add_plugin(PausePlugin)
             // .add_plugin(GoalPlugin)
             .add_plugin(AltGoalPlugin)
             .add_plugin(ConsolePlugin)
+            .add_plugin(LifelinesPlugin)
             // .add_plugin(GameOverPlugin)
             .add_plugin(end::EndPlugin)
+            .add_plugin(LeaderboardPlugin)
+            .add_plugin(PrizesPlugin)
             .add_plugin(despawn::DespawnPlugin);
 
         #[cfg(debug_assertions)]
@@ -0,0 +1,86 @@
+use crate::actions::UiAction;
+use crate::console::ConsoleConfig;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+
+pub struct LifelinesPlugin;
+
+/// Refills the player's lifelines from the console's `lifelines` stock at the
+/// start of every session. They are spent in the console, see `console::systems`.
+impl Plugin for LifelinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lifelines>()
+            .add_system(restock.in_schedule(OnExit(GameState::Controls)));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Lifeline {
+    /// Removes half of the wrong options
+    FiftyFifty,
+    /// Swaps the question for a fresh one without counting it as wrong
+    Skip,
+    /// Winds the level clock back by the console's `extra_time`
+    ExtraTime,
+}
+
+impl Lifeline {
+    pub const ALL: [Lifeline; 3] = [Lifeline::FiftyFifty, Lifeline::Skip, Lifeline::ExtraTime];
+
+    pub fn action(&self) -> UiAction {
+        match self {
+            Lifeline::FiftyFifty => UiAction::FiftyFifty,
+            Lifeline::Skip => UiAction::Skip,
+            Lifeline::ExtraTime => UiAction::ExtraTime,
+        }
+    }
+}
+
+/// How many of each lifeline the player has left. A lifeline left out of the
+/// console config gets its count from [`Lifelines::default`].
+#[derive(serde::Deserialize, Resource, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Lifelines {
+    pub fifty_fifty: u32,
+    pub skip: u32,
+    pub extra_time: u32,
+}
+
+impl Default for Lifelines {
+    fn default() -> Self {
+        Lifelines {
+            fifty_fifty: 1,
+            skip: 1,
+            extra_time: 1,
+        }
+    }
+}
+
+impl Lifelines {
+    pub fn count(&self, lifeline: Lifeline) -> u32 {
+        match lifeline {
+            Lifeline::FiftyFifty => self.fifty_fifty,
+            Lifeline::Skip => self.skip,
+            Lifeline::ExtraTime => self.extra_time,
+        }
+    }
+
+    /// Uses up one `lifeline`, returning false if none were left
+    pub fn take(&mut self, lifeline: Lifeline) -> bool {
+        let count = match lifeline {
+            Lifeline::FiftyFifty => &mut self.fifty_fifty,
+            Lifeline::Skip => &mut self.skip,
+            Lifeline::ExtraTime => &mut self.extra_time,
+        };
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+}
+
+fn restock(mut lifelines: ResMut<Lifelines>, config: Res<ConsoleConfig>) {
+    *lifelines = config.lifelines.clone();
+}
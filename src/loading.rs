@@ -1,3 +1,6 @@
+use crate::console::ConsoleConfig;
+use crate::rules::GameRules;
+use crate::trivia::TriviaLoader;
 use crate::GameState;
 use bevy::asset::Error;
 use bevy::prelude::*;
@@ -7,8 +10,9 @@ use bevy_asset_loader::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
 use bevy_ecs_ldtk::LdtkAsset;
 use bevy_kira_audio::AudioSource;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::path::PathBuf;
 
 pub struct LoadingPlugin;
 
@@ -20,36 +24,108 @@ impl Plugin for LoadingPlugin {
         app.add_plugin(RonAssetPlugin::<GameDataAssetDynamicCollection>::new(&[
             "game-data.ron",
         ]))
+        .add_asset_loader(TriviaLoader)
+        .add_plugin(RonAssetPlugin::<ConsoleConfig>::new(&["console.ron"]))
+        .add_plugin(RonAssetPlugin::<GameRules>::new(&["rules.ron"]))
         .add_asset::<Question>()
         .add_loading_state(LoadingState::new(GameState::Loading).continue_to_state(GameState::Menu))
         .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, FontAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, TextureAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, LevelAssets>(GameState::Loading)
-        .add_dynamic_collection_to_loading_state::<_, GameDataAssetDynamicCollection>(
-            GameState::Loading,
-            "data/trivia.game-data.ron",
-        )
-        .add_collection_to_loading_state::<_, QuestionAssets>(GameState::Loading);
+        .add_collection_to_loading_state::<_, QuestionPacks>(GameState::Loading)
+        .add_collection_to_loading_state::<_, ConsoleConfigAssets>(GameState::Loading)
+        .add_collection_to_loading_state::<_, GameRulesAssets>(GameState::Loading)
+        .init_resource::<QuestionRegistry>()
+        .add_system(register_questions.in_schedule(OnExit(GameState::Loading)));
     }
 }
 
-#[derive(serde::Deserialize, bevy::reflect::TypeUuid)]
-#[uuid = "2df00c92-cf7b-42c1-a989-dccbad659c13"]
-pub struct GameDataAssetDynamicCollection(HashMap<String, GameDataAsset>);
+/// Builds every entry of every loaded question pack into [`Assets<Question>`]
+/// and indexes the resulting handles by their key in the [`QuestionRegistry`],
+/// which also holds on to the question images this starts loading.
+///
+/// Packs are processed in asset path order, the same order `validate-packs`
+/// checks them in, so a key defined in more than one pack always comes from the
+/// first of them whatever order the packs finished loading in.
+fn register_questions(world: &mut World) {
+    let asset_server = world.resource::<AssetServer>().clone();
+    let entries: Vec<(String, String, GameDataAsset)> = {
+        let packs = world.resource::<QuestionPacks>();
+        let collections = world.resource::<Assets<GameDataAssetDynamicCollection>>();
+        let mut loaded: Vec<(PathBuf, &GameDataAssetDynamicCollection)> = packs
+            .packs
+            .iter()
+            .filter_map(|handle| {
+                let path = asset_server
+                    .get_handle_path(handle)
+                    .map_or_else(PathBuf::new, |path| path.path().to_path_buf());
+                collections.get(handle).map(|collection| (path, collection))
+            })
+            .collect();
+        loaded.sort_by(|(a, _), (b, _)| a.cmp(b));
+        loaded
+            .into_iter()
+            .flat_map(|(path, collection)| {
+                let pack = path
+                    .file_name()
+                    .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+                collection
+                    .0
+                    .clone()
+                    .into_iter()
+                    .map(move |(key, asset)| (pack.clone(), key, asset))
+            })
+            .collect()
+    };
 
-impl DynamicAssetCollection for GameDataAssetDynamicCollection {
-    fn register(&self, dynamic_assets: &mut DynamicAssets) {
-        for (key, asset) in self.0.iter() {
-            dynamic_assets.register_asset(key, Box::new(asset.clone()))
+    for (pack, key, asset) in entries {
+        if let Some(first_pack) = world.resource::<QuestionRegistry>().packs.get(&key) {
+            warn!(
+                "Question '{}' in {} is already defined in {}, skipping it",
+                key, pack, first_pack
+            );
+            continue;
         }
+        let images = asset.load(&asset_server);
+        world
+            .resource_mut::<QuestionRegistry>()
+            .images
+            .extend(images);
+        match asset.build(world) {
+            Ok(DynamicAssetType::Single(handle)) => {
+                let mut registry = world.resource_mut::<QuestionRegistry>();
+                registry.questions.insert(key.clone(), handle.typed());
+                registry.packs.insert(key, pack);
+            }
+            Ok(DynamicAssetType::Collection(_)) => {
+                warn!("Question '{}' did not resolve to a single asset", key);
+            }
+            Err(error) => error!("Failed to build question '{}': {}", key, error),
+        }
+    }
+
+    if world.resource::<QuestionRegistry>().is_empty() {
+        warn!("No questions were found in 'assets/data/packs'");
     }
+    info!(
+        "Registered {} questions from {} pack(s)",
+        world.resource::<QuestionRegistry>().len(),
+        world.resource::<QuestionPacks>().packs.len()
+    );
 }
 
+#[derive(serde::Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "2df00c92-cf7b-42c1-a989-dccbad659c13"]
+pub struct GameDataAssetDynamicCollection(pub(crate) HashMap<String, GameDataAsset>);
+
 impl DynamicAsset for GameDataAsset {
-    fn load(&self, _asset_server: &AssetServer) -> Vec<HandleUntyped> {
+    fn load(&self, asset_server: &AssetServer) -> Vec<HandleUntyped> {
         match self {
-            GameDataAsset::Question { .. } => vec![],
+            GameDataAsset::Question { image, .. } => image
+                .iter()
+                .map(|path| asset_server.load_untyped(path.as_str()))
+                .collect(),
         }
     }
 
@@ -59,9 +135,29 @@ impl DynamicAsset for GameDataAsset {
         match self {
             GameDataAsset::Question {
                 description,
+                kind,
                 options,
                 answer,
+                category,
+                difficulty,
+                explanation,
+                source,
+                tags,
+                image,
+                time_limit,
             } => {
+                // The console divides by the step and clamps the dial to the range
+                if let QuestionKind::Numeric { min, max, step, .. } = kind {
+                    if !QuestionKind::is_valid_range(*min, *max, *step) {
+                        return Err(Error::msg(format!(
+                            "numeric range {}..={} with step {} is empty",
+                            min, max, step
+                        )));
+                    }
+                }
+                let asset_server = cell
+                    .get_resource::<AssetServer>()
+                    .expect("Failed to get asset server");
                 let mut questions = cell
                     .get_resource_mut::<Assets<Question>>()
                     .expect("Failed to get question asset");
@@ -69,8 +165,18 @@ impl DynamicAsset for GameDataAsset {
                 let handle = questions
                     .add(Question {
                         description: description.clone(),
-                        options: options.clone(),
+                        kind: kind.clone(),
+                        options: kind.options_or_default(options),
                         answer: answer.clone(),
+                        category: category.clone(),
+                        difficulty: *difficulty,
+                        explanation: explanation.clone(),
+                        source: source.clone(),
+                        tags: tags.clone(),
+                        image: image
+                            .as_ref()
+                            .map(|path| asset_server.get_handle(path.as_str())),
+                        time_limit: *time_limit,
                         used: false,
                     })
                     .clone_untyped();
@@ -80,83 +186,230 @@ impl DynamicAsset for GameDataAsset {
     }
 }
 
+/// Everything but `description` is optional, so packs written before these
+/// fields existed keep loading unchanged. `options` can be left out where the
+/// kind does not need them, `answer` where the kind does not use it.
 #[derive(serde::Deserialize, Debug, Clone)]
-enum GameDataAsset {
+pub(crate) enum GameDataAsset {
     Question {
         description: String,
+        #[serde(default)]
+        kind: QuestionKind,
+        #[serde(default)]
         options: Vec<String>,
+        /// The correct option, or the number for [`QuestionKind::Numeric`].
+        /// Unused by [`QuestionKind::SelectAll`] and [`QuestionKind::Ordering`].
+        #[serde(default)]
         answer: String,
+        #[serde(default)]
+        category: Option<String>,
+        #[serde(default)]
+        difficulty: Option<Difficulty>,
+        /// Shown to the player once the question has been answered
+        #[serde(default)]
+        explanation: Option<String>,
+        /// Attribution for where the question or its facts came from
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+        /// Path of a picture shown above the options, relative to `assets/`
+        #[serde(default)]
+        image: Option<String>,
+        /// Seconds to answer, overriding the console's `answer_time`
+        #[serde(default)]
+        time_limit: Option<f32>,
     },
 }
 
-#[derive(TypeUuid, Clone, PartialEq, Eq, Hash, Debug)]
+/// How a question is answered and graded in the console
+#[derive(serde::Deserialize, Clone, PartialEq, Debug, Default)]
+pub enum QuestionKind {
+    /// Pick the one option matching `answer`
+    #[default]
+    SingleChoice,
+    /// `answer` is "True" or "False"; `options` may be left out
+    TrueFalse,
+    /// Toggle every option listed in `answers`, then confirm
+    SelectAll { answers: Vec<String> },
+    /// `options` are written in the correct order and shown shuffled
+    Ordering,
+    /// Dial in a number within `tolerance` of `answer`
+    Numeric {
+        min: f64,
+        max: f64,
+        #[serde(default = "default_numeric_step")]
+        step: f64,
+        #[serde(default)]
+        tolerance: f64,
+    },
+}
+
+fn default_numeric_step() -> f64 {
+    1.
+}
+
+impl QuestionKind {
+    pub fn options_or_default(&self, options: &[String]) -> Vec<String> {
+        match self {
+            QuestionKind::TrueFalse if options.is_empty() => {
+                vec!["True".to_string(), "False".to_string()]
+            }
+            _ => options.to_vec(),
+        }
+    }
+
+    /// Whether a [`QuestionKind::Numeric`] dial can move between `min` and `max`
+    /// in steps of `step`. False for NaN too.
+    pub fn is_valid_range(min: f64, max: f64, step: f64) -> bool {
+        min < max && step > 0.
+    }
+
+    /// Kinds that need an explicit confirmation button next to their options
+    pub fn has_submit_button(&self) -> bool {
+        matches!(self, QuestionKind::SelectAll { .. })
+    }
+}
+
+#[derive(serde::Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+#[derive(TypeUuid, Clone, PartialEq, Debug)]
 #[uuid = "2a897eae-c084-4fcd-bfb4-f54c64d2895e"]
 pub struct Question {
     pub description: String,
+    pub kind: QuestionKind,
     pub options: Vec<String>,
     pub answer: String,
+    pub category: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub explanation: Option<String>,
+    pub source: Option<String>,
+    pub tags: Vec<String>,
+    pub image: Option<Handle<Image>>,
+    pub time_limit: Option<f32>,
     pub used: bool,
 }
 
+impl Question {
+    /// Position in `options` of the correct option of single-choice and true/false questions
+    pub fn answer_index(&self) -> Option<usize> {
+        self.options
+            .iter()
+            .position(|option| *option == self.answer)
+    }
+}
+
 impl fmt::Display for Question {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}\n{:?}", self.answer, self.description)
     }
 }
 
-#[derive(AssetCollection, Resource)]
-pub struct QuestionAssets {
-    #[asset(key = "pac_man")]
-    pub pac_man: Handle<Question>,
-    #[asset(key = "frogger")]
-    pub frogger: Handle<Question>,
-    #[asset(key = "yoshi")]
-    pub yoshi: Handle<Question>,
-    #[asset(key = "pong")]
-    pub pong: Handle<Question>,
-    #[asset(key = "nintendo_wii")]
-    pub nintendo_wii: Handle<Question>,
-    #[asset(key = "pokemon_go")]
-    pub pokemon_go: Handle<Question>,
-    #[asset(key = "duck_hunt")]
-    pub duck_hunt: Handle<Question>,
-    #[asset(key = "clyde")]
-    pub clyde: Handle<Question>,
-    #[asset(key = "chuck_e_cheese")]
-    pub chuck_e_cheese: Handle<Question>,
-    #[asset(key = "the_sims")]
-    pub the_sims: Handle<Question>,
-    #[asset(key = "bulbasaur")]
-    pub bulbasaur: Handle<Question>,
-    #[asset(key = "first_bash")]
-    pub first_bash: Handle<Question>,
-    #[asset(key = "mega_event")]
-    pub mega_event: Handle<Question>,
-    #[asset(key = "golf_ball")]
-    pub golf_ball: Handle<Question>,
-    #[asset(key = "area_51")]
-    pub area_51: Handle<Question>,
-    #[asset(key = "pennsylvania")]
-    pub pennsylvania: Handle<Question>,
-    #[asset(key = "wauseon")]
-    pub wauseon: Handle<Question>,
-    #[asset(key = "tin_foil_hat")]
-    pub tin_foil_hat: Handle<Question>,
-    #[asset(key = "hat_on")]
-    pub hat_on: Handle<Question>,
-    #[asset(key = "swag")]
-    pub swag: Handle<Question>,
-    #[asset(key = "favorited")]
-    pub favorited: Handle<Question>,
-    #[asset(key = "orginial")]
-    pub orginial: Handle<Question>,
-    #[asset(key = "no_geobash")]
-    pub no_geobash: Handle<Question>,
+/// All question handles known to the game, keyed by the name they were given
+/// in their `*.game-data.ron` pack. Iterates in key order, so a seeded
+/// [`SessionRng`](crate::random::SessionRng) always picks the same questions.
+///
+/// Staff can limit the game to a single pack from the admin panel, after which
+/// only that pack's questions are iterated.
+#[derive(Resource, Default)]
+pub struct QuestionRegistry {
+    questions: BTreeMap<String, Handle<Question>>,
+    /// File name of the pack each key was first defined in
+    packs: BTreeMap<String, String>,
+    /// `None` while questions from every pack are asked
+    active_pack: Option<String>,
+    /// Keeps the images of the questions loaded for as long as the game runs
+    images: Vec<HandleUntyped>,
+}
+
+impl QuestionRegistry {
+    /// The questions of the active pack
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Handle<Question>)> {
+        self.questions.iter().filter(|(key, _)| {
+            self.active_pack
+                .as_ref()
+                .is_none_or(|active| self.packs.get(*key) == Some(active))
+        })
+    }
+
+    /// File names of the packs that define at least one question, in order
+    pub fn pack_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.packs.values().map(String::as_str).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    pub fn active_pack(&self) -> Option<&str> {
+        self.active_pack.as_deref()
+    }
+
+    /// Limits the questions to those of `pack`, or allows every pack with `None`
+    pub fn set_active_pack(&mut self, pack: Option<String>) {
+        info!(
+            "Asking questions from {}",
+            pack.as_deref().unwrap_or("every pack")
+        );
+        self.active_pack = pack;
+    }
+
+    /// The key `handle` was registered under
+    pub fn key(&self, handle: &Handle<Question>) -> Option<&str> {
+        self.questions
+            .iter()
+            .find(|(_, registered)| *registered == handle)
+            .map(|(key, _)| key.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
 }
 
 // the following asset collections will be loaded during the State `GameState::Loading`
 // when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
 
+/// Every question pack found in `assets/data/packs`. Drop additional
+/// `*.game-data.ron`, `*.trivia.csv` or `*.trivia.json` files into that directory
+/// to extend or replace the trivia.
+///
+/// Folders can not be listed on the web, so wasm builds load the bundled pack only.
+#[derive(AssetCollection, Resource)]
+pub struct QuestionPacks {
+    #[cfg_attr(
+        not(target_arch = "wasm32"),
+        asset(path = "data/packs", collection(typed))
+    )]
+    #[cfg_attr(
+        target_arch = "wasm32",
+        asset(paths("data/packs/trivia.game-data.ron"), collection(typed))
+    )]
+    pub packs: Vec<Handle<GameDataAssetDynamicCollection>>,
+}
+
+/// Per-event settings of the quiz console; edit the file to suit the event
+#[derive(AssetCollection, Resource)]
+pub struct ConsoleConfigAssets {
+    #[asset(path = "data/event.console.ron")]
+    pub config: Handle<ConsoleConfig>,
+}
+
+/// Score, time and lives of a session; edit the file to suit the event
+#[derive(AssetCollection, Resource)]
+pub struct GameRulesAssets {
+    #[asset(path = "data/event.rules.ron")]
+    pub rules: Handle<GameRules>,
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct FontAssets {
     #[asset(path = "fonts/FiraSans-Bold.ttf")]
@@ -21,6 +21,8 @@ fn main() {
                         canvas: Some("#bevy".to_owned()),
                         ..default()
                     }),
+                    // Closing is left to the game so kiosk mode can refuse it
+                    close_when_requested: false,
                     ..default()
                 })
                 .set(ImagePlugin::default_nearest()),
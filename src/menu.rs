@@ -1,4 +1,5 @@
 use crate::actions::UiAction;
+use crate::admin::AdminPanel;
 use crate::loading::{FontAssets, TextureAssets};
 use crate::{GameState, LevelState};
 use bevy::prelude::*;
@@ -12,7 +13,11 @@ impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ButtonColors>()
             .add_system(setup_menu.in_schedule(OnEnter(GameState::Menu)))
-            .add_system(click_play_button.in_set(OnUpdate(GameState::Menu)))
+            .add_system(
+                click_play_button
+                    .run_if(not(resource_exists::<AdminPanel>()))
+                    .in_set(OnUpdate(GameState::Menu)),
+            )
             .add_system(cleanup_menu.in_schedule(OnExit(GameState::Menu)))
             .add_system(setup_controls_menu.in_schedule(OnEnter(GameState::Controls)))
             .add_system(click_control_play_button.in_set(OnUpdate(GameState::Controls)))
@@ -47,8 +52,12 @@ fn setup_menu(
     textures: Res<TextureAssets>,
     font_assets: Res<FontAssets>,
     button_colors: Res<ButtonColors>,
+    cameras: Query<(), With<Camera2d>>,
 ) {
-    commands.spawn(Camera2dBundle::default());
+    // The menu is shown again when a kiosk goes idle, by then the camera exists
+    if cameras.is_empty() {
+        commands.spawn(Camera2dBundle::default());
+    }
     commands
         .spawn((
             NodeBundle {
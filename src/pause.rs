@@ -0,0 +1,336 @@
+use crate::actions::UiAction;
+use crate::clock::TimeScale;
+use crate::loading::FontAssets;
+use crate::{GameState, LevelState};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+const HIGHLIGHT_COLOR: Color = Color::rgb(1.0, 0.925, 0.153);
+
+pub struct PausePlugin;
+
+/// Start pauses the overworld, a question or its reveal behind a menu, and
+/// resuming goes back to where it was paused. While paused the [`TimeScale`] is
+/// zero, which stops the clock and scaled scheduled events, the Rapier pipeline
+/// is off and sprite sheet animations hold their frame. The answer and reveal
+/// timers only tick in their own states, so they wait too.
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        // After the level's own systems, so a state change they started this frame
+        // goes ahead. The end screens are shown over the overworld, so check the
+        // game state too
+        app.add_system(
+            pause
+                .in_base_set(CoreSet::PostUpdate)
+                .run_if(in_state(GameState::Playing))
+                .run_if(can_pause),
+        )
+        .add_system(setup_pause_menu.in_schedule(OnEnter(LevelState::Paused)))
+        .add_system(use_pause_menu.in_set(OnUpdate(LevelState::Paused)))
+        .add_system(cleanup_pause_menu.in_schedule(OnExit(LevelState::Paused)))
+        .add_system(forget_pause_menu.run_if(not_paused));
+    }
+}
+
+/// The options on the pause menu, in the order they are listed
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum PauseOption {
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+
+const OPTIONS: [PauseOption; 3] = [
+    PauseOption::Resume,
+    PauseOption::Restart,
+    PauseOption::QuitToMenu,
+];
+
+impl PauseOption {
+    fn label(self) -> &'static str {
+        match self {
+            PauseOption::Resume => "RESUME",
+            PauseOption::Restart => "RESTART",
+            PauseOption::QuitToMenu => "QUIT TO MENU",
+        }
+    }
+}
+
+/// Run condition for systems that have to hold still behind the pause menu.
+/// Also keeps the exit of a level state from tearing it down when it is only
+/// paused, as the new state is already set while the old one exits.
+pub fn not_paused(level_state: Res<State<LevelState>>) -> bool {
+    level_state.0 != LevelState::Paused
+}
+
+/// Run condition for the setup of a level state, which is already in place when
+/// the pause menu resumes it
+pub fn not_resuming(menu: Option<Res<PauseMenu>>) -> bool {
+    !menu.is_some_and(|menu| menu.resumed)
+}
+
+fn can_pause(level_state: Res<State<LevelState>>) -> bool {
+    matches!(
+        level_state.0,
+        LevelState::OverWorld | LevelState::Console | LevelState::Reveal
+    )
+}
+
+/// Inserted when the game pauses and kept until the frame after it resumes
+#[derive(Resource)]
+pub struct PauseMenu {
+    selected: usize,
+    /// Put back on resume, so a rewind carries on where it was paused
+    time_scale: f32,
+    /// The level state the game was paused in
+    resume_to: LevelState,
+    resumed: bool,
+}
+
+#[derive(Component)]
+struct PauseMenuRoot;
+
+fn pause(
+    mut commands: Commands,
+    actions: Query<&ActionState<UiAction>>,
+    time_scale: Res<TimeScale>,
+    current: Res<State<LevelState>>,
+    mut level_state: ResMut<NextState<LevelState>>,
+) {
+    if level_state.0.is_some() {
+        return;
+    }
+    // Start and select share a key, and select answers questions
+    let in_overworld = current.0 == LevelState::OverWorld;
+    if actions.iter().any(|action_state| {
+        action_state.just_pressed(UiAction::Start)
+            && (in_overworld || !action_state.just_pressed(UiAction::Select))
+    }) {
+        info!("Pausing");
+        commands.insert_resource(PauseMenu {
+            selected: 0,
+            time_scale: time_scale.0,
+            resume_to: current.0,
+            resumed: false,
+        });
+        level_state.set(LevelState::Paused);
+    }
+}
+
+fn setup_pause_menu(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    mut time_scale: ResMut<TimeScale>,
+    mut rapier: ResMut<RapierConfiguration>,
+) {
+    time_scale.0 = 0.;
+    rapier.physics_pipeline_active = false;
+
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: fonts.crt_font.clone(),
+        font_size,
+        color,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    gap: Size::height(Val::Px(16.)),
+                    ..Default::default()
+                },
+                background_color: Color::rgba_u8(22, 23, 26, 200).into(),
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            PauseMenuRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "PAUSED",
+                text_style(72., Color::PINK),
+            ));
+            for (index, option) in OPTIONS.iter().enumerate() {
+                let color = if index == 0 {
+                    HIGHLIGHT_COLOR
+                } else {
+                    Color::WHITE
+                };
+                parent.spawn((
+                    TextBundle::from_section(option.label(), text_style(40., color)),
+                    *option,
+                ));
+            }
+        });
+}
+
+fn use_pause_menu(
+    actions: Query<&ActionState<UiAction>>,
+    mut menu: ResMut<PauseMenu>,
+    mut options: Query<(&mut Text, &PauseOption)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut level_state: ResMut<NextState<LevelState>>,
+) {
+    for action_state in &actions {
+        // Start and select share a key, so select is checked first
+        let chosen = if action_state.just_pressed(UiAction::Select) {
+            Some(OPTIONS[menu.selected])
+        } else if action_state.just_pressed(UiAction::Start) {
+            Some(PauseOption::Resume)
+        } else {
+            None
+        };
+        match chosen {
+            Some(PauseOption::Resume) => {
+                info!("Resuming");
+                level_state.set(menu.resume_to);
+            }
+            Some(PauseOption::Restart) => {
+                info!("Restarting from the pause menu");
+                level_state.set(LevelState::None);
+                game_state.set(GameState::Controls);
+            }
+            Some(PauseOption::QuitToMenu) => {
+                info!("Quitting to the menu");
+                level_state.set(LevelState::None);
+                game_state.set(GameState::Menu);
+            }
+            None if action_state.just_pressed(UiAction::Up) => {
+                menu.selected = menu.selected.saturating_sub(1);
+            }
+            None if action_state.just_pressed(UiAction::Down) => {
+                menu.selected = (menu.selected + 1).min(OPTIONS.len() - 1);
+            }
+            None => {}
+        }
+    }
+
+    if menu.is_changed() {
+        for (mut text, option) in &mut options {
+            text.sections[0].style.color = if OPTIONS[menu.selected] == *option {
+                HIGHLIGHT_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+fn cleanup_pause_menu(
+    mut commands: Commands,
+    menu: Option<ResMut<PauseMenu>>,
+    mut time_scale: ResMut<TimeScale>,
+    mut rapier: ResMut<RapierConfiguration>,
+    roots: Query<Entity, With<PauseMenuRoot>>,
+) {
+    if let Some(mut menu) = menu {
+        time_scale.0 = menu.time_scale;
+        menu.resumed = true;
+    }
+    rapier.physics_pipeline_active = true;
+    for root in &roots {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/// Drops the menu once the state it resumed has been entered
+fn forget_pause_menu(mut commands: Commands, menu: Option<Res<PauseMenu>>) {
+    if menu.is_some_and(|menu| menu.resumed) {
+        commands.remove_resource::<PauseMenu>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// How often the console was set up and torn down
+    #[derive(Resource, Default)]
+    struct ConsoleChanges {
+        setups: usize,
+        teardowns: usize,
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<GameState>()
+            .add_state::<LevelState>()
+            .add_plugin(PausePlugin)
+            .insert_resource(TimeScale(1.))
+            .insert_resource(RapierConfiguration::default())
+            .insert_resource(FontAssets {
+                fira_sans: default(),
+                crt_font: default(),
+                monogram: default(),
+                pico: default(),
+                pixel_font: default(),
+                gamepad_font: default(),
+            })
+            .init_resource::<ConsoleChanges>()
+            .add_system(
+                (|mut changes: ResMut<ConsoleChanges>| changes.setups += 1)
+                    .run_if(not_resuming)
+                    .in_schedule(OnEnter(LevelState::Console)),
+            )
+            .add_system(
+                (|mut changes: ResMut<ConsoleChanges>| changes.teardowns += 1)
+                    .run_if(not_paused)
+                    .in_schedule(OnExit(LevelState::Console)),
+            );
+        app.world.spawn(ActionState::<UiAction>::default());
+        app.insert_resource(NextState(Some(GameState::Playing)))
+            .insert_resource(NextState(Some(LevelState::Console)));
+        app.update();
+        app
+    }
+
+    /// Taps start, taking a frame to press and one to release
+    fn tap_start(app: &mut App) {
+        let mut actions = app.world.query::<&mut ActionState<UiAction>>();
+        actions.single_mut(&mut app.world).press(UiAction::Start);
+        app.update();
+        actions.single_mut(&mut app.world).release(UiAction::Start);
+        app.update();
+    }
+
+    fn level_state(app: &App) -> LevelState {
+        app.world.resource::<State<LevelState>>().0
+    }
+
+    #[test]
+    fn pausing_a_question_resumes_it_as_it_was() {
+        let mut app = app();
+
+        tap_start(&mut app);
+        assert_eq!(level_state(&app), LevelState::Paused);
+        assert_eq!(app.world.resource::<TimeScale>().0, 0.);
+
+        tap_start(&mut app);
+        assert_eq!(level_state(&app), LevelState::Console);
+        assert_eq!(app.world.resource::<TimeScale>().0, 1.);
+        let changes = app.world.resource::<ConsoleChanges>();
+        assert_eq!((changes.setups, changes.teardowns), (1, 0));
+
+        app.update();
+        assert!(!app.world.contains_resource::<PauseMenu>());
+    }
+
+    #[test]
+    fn select_answers_a_question_instead_of_pausing() {
+        let mut app = app();
+        let mut actions = app.world.query::<&mut ActionState<UiAction>>();
+        let mut action_state = actions.single_mut(&mut app.world);
+        action_state.press(UiAction::Start);
+        action_state.press(UiAction::Select);
+        app.update();
+        app.update();
+
+        assert_eq!(level_state(&app), LevelState::Console);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::player::{ColliderBundle, PlayerAction, Vitality};
+use crate::clock::History;
+use crate::pause::not_paused;
+use crate::player::rewind::{not_rewinding, Motion};
+use crate::player::{ColliderBundle, Lives, PlayerAction, Vitality};
+use crate::rules::GameRules;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
@@ -58,6 +62,9 @@ impl Default for PlayerInput {
         input_map.insert(KeyCode::D, Right);
         input_map.insert(GamepadButtonType::DPadRight, Right);
 
+        input_map.insert(KeyCode::R, Rewind);
+        input_map.insert(GamepadButtonType::LeftTrigger, Rewind);
+
         Self {
             input_manager: InputManagerBundle::<PlayerAction> {
                 input_map,
@@ -86,6 +93,8 @@ pub struct PlayerAltBundle {
     player: PlayerAlt,
     // animation: PlayerAnimationState,
     vitality: Vitality,
+    lives: Lives,
+    history: History<Motion>,
 
     #[bundle]
     pub input: PlayerInput,
@@ -101,7 +110,13 @@ impl Plugin for PlayerAltPlugin {
             //     PlayerAnimationState,
             //     SpriteSheetAnimation,
             // >::new())
-            .add_system(apply_alt_actions.in_set(OnUpdate(GameState::Playing)));
+            .add_system(fill_lives)
+            .add_system(
+                apply_alt_actions
+                    .run_if(not_rewinding)
+                    .run_if(not_paused)
+                    .in_set(OnUpdate(GameState::Playing)),
+            );
     }
 }
 //
@@ -188,3 +203,10 @@ fn apply_alt_actions(
         }
     }
 }
+
+/// Gives a freshly spawned player the hearts the rules start them with
+fn fill_lives(rules: Res<GameRules>, mut lives: Query<&mut Lives, Added<Lives>>) {
+    for mut lives in &mut lives {
+        lives.hearts = rules.lives;
+    }
+}
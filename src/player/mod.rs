@@ -1,5 +1,6 @@
 pub mod alt;
 mod entities;
+pub mod rewind;
 
 use crate::animation::{FromComponentPlugin, SpriteSheetAnimation};
 use crate::GameState;
@@ -32,6 +33,25 @@ pub enum Vitality {
     Dead,
 }
 
+/// Hearts the player has left; the game is lost once they run out
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Lives {
+    pub hearts: f32,
+}
+
+impl Lives {
+    /// Hearts lost for every wrong answer
+    pub const WRONG_ANSWER_DAMAGE: f32 = 0.5;
+
+    pub fn lose(&mut self, hearts: f32) {
+        self.hearts = (self.hearts - hearts).max(0.);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hearts <= 0.
+    }
+}
+
 impl From<PlayerAnimationState> for SpriteSheetAnimation {
     fn from(animation_state: PlayerAnimationState) -> Self {
         let indices = match animation_state {
@@ -167,6 +187,7 @@ pub enum PlayerAction {
     Down,
     Left,
     Right,
+    Rewind,
 }
 
 fn apply_actions(
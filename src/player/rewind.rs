@@ -0,0 +1,140 @@
+use crate::clock::{update_time, History, Moment, TimeScale, TimeSinceLevelStart};
+use crate::pause::not_paused;
+use crate::player::{PlayerAction, Vitality};
+use crate::{GameState, LevelState};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+/// How much faster than real time the clock runs backwards while rewinding
+const REWIND_SPEED: f32 = 2.;
+
+pub struct RewindPlugin;
+
+/// Records where the flamingo was on every frame. Holding [`PlayerAction::Rewind`]
+/// runs the [`TimeScale`] backwards, which refunds the clock and scrubs the
+/// flamingo back along its path.
+///
+/// Answers can not be taken back, so the recording starts over at every question.
+/// Pausing holds a rewind where it is.
+impl Plugin for RewindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rewinding>()
+            .add_systems(
+                (
+                    control_rewind.before(update_time),
+                    record_motion.after(update_time),
+                    replay_motion.after(update_time),
+                )
+                    .distributive_run_if(not_paused)
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_system(forget_motion.in_schedule(OnEnter(LevelState::Console)));
+    }
+}
+
+/// What is recorded of the flamingo on every frame
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Motion {
+    pub translation: Vec3,
+    pub linvel: Vec2,
+}
+
+/// Set while the clock is running backwards
+#[derive(Resource, Default, Debug)]
+pub struct Rewinding(pub bool);
+
+/// Run condition for systems that move the flamingo themselves
+pub fn not_rewinding(rewinding: Res<Rewinding>) -> bool {
+    !rewinding.0
+}
+
+fn control_rewind(
+    mut rewinding: ResMut<Rewinding>,
+    mut time_scale: ResMut<TimeScale>,
+    level_state: Res<State<LevelState>>,
+    players: Query<(&ActionState<PlayerAction>, &History<Motion>, &Vitality)>,
+) {
+    let can_rewind = level_state.0 == LevelState::OverWorld
+        && players.iter().any(|(action_state, history, vitality)| {
+            action_state.pressed(PlayerAction::Rewind)
+                && !history.moments.is_empty()
+                && *vitality == Vitality::Alive
+        });
+
+    if rewinding.0 && !can_rewind {
+        rewinding.0 = false;
+        time_scale.0 = 1.;
+    } else if !rewinding.0 && can_rewind && time_scale.0 > 0. {
+        rewinding.0 = true;
+        time_scale.0 = -REWIND_SPEED;
+    }
+}
+
+fn record_motion(
+    rewinding: Res<Rewinding>,
+    time_scale: Res<TimeScale>,
+    time_since_level_start: Res<TimeSinceLevelStart>,
+    level_state: Res<State<LevelState>>,
+    mut players: Query<(&mut History<Motion>, &Transform, &Velocity)>,
+) {
+    if rewinding.0 || time_scale.0 <= 0. || level_state.0 != LevelState::OverWorld {
+        return;
+    }
+    for (mut history, transform, velocity) in &mut players {
+        history.moments.push(Moment {
+            timestamp: time_since_level_start.0,
+            data: Motion {
+                translation: transform.translation,
+                linvel: velocity.linvel,
+            },
+        });
+    }
+}
+
+/// Drops the moments the clock has run back past and puts the flamingo where
+/// the latest remaining one says
+fn replay_motion(
+    rewinding: Res<Rewinding>,
+    time_since_level_start: Res<TimeSinceLevelStart>,
+    mut players: Query<(
+        &mut History<Motion>,
+        &mut Transform,
+        &mut Velocity,
+        &mut TextureAtlasSprite,
+    )>,
+) {
+    if !rewinding.0 {
+        return;
+    }
+    for (mut history, mut transform, mut velocity, mut sprite) in &mut players {
+        let mut position = None;
+        while history
+            .moments
+            .last()
+            .is_some_and(|moment| moment.timestamp > time_since_level_start.0)
+        {
+            position = history.moments.pop().map(|moment| moment.data);
+        }
+        let Some(motion) = history
+            .moments
+            .last()
+            .map(|moment| moment.data)
+            .or(position)
+        else {
+            continue;
+        };
+        transform.translation = motion.translation;
+        // Rapier would otherwise carry on moving the body between frames
+        velocity.linvel = Vec2::ZERO;
+        if motion.linvel.x.abs() > 0. {
+            sprite.flip_x = motion.linvel.x > 0.;
+        }
+    }
+}
+
+fn forget_motion(mut histories: Query<&mut History<Motion>>) {
+    for mut history in &mut histories {
+        history.moments.clear();
+    }
+}
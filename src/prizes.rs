@@ -0,0 +1,321 @@
+//! Claim codes for the "1 path tag per family" prize.
+//!
+//! Every win is given a short code like `7KQ2-M9XD`: a random serial followed
+//! by a signature of that serial made with the event secret. Volunteers check
+//! codes offline with the `redeem-prize` tool, which rejects codes that were
+//! not signed with the secret and codes that were already redeemed.
+//!
+//! The secret is read from the `FLAMINGO_EVENT_SECRET` environment variable.
+//! Without it, the game creates a secret once and keeps it in its data directory,
+//! so codes can still be checked on the machine that issued them. The checker
+//! never creates one.
+
+use crate::storage;
+use bevy::prelude::*;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::{env, io};
+
+/// Environment variable holding the per-event secret
+pub const SECRET_VARIABLE: &str = "FLAMINGO_EVENT_SECRET";
+
+/// Crockford's base 32, which leaves out letters easily mistaken for digits
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Characters in each half of a code, 20 bits each
+const PART_LENGTH: usize = 4;
+
+/// Attempts at finding a serial that was not issued before
+const MAX_ATTEMPTS: usize = 16;
+
+const SECRET_FILE: &str = "event-secret";
+const ISSUED_FILE: &str = "claim-codes.log";
+const REDEEMED_FILE: &str = "redeemed-codes.log";
+
+pub struct PrizesPlugin;
+
+impl Plugin for PrizesPlugin {
+    fn build(&self, app: &mut App) {
+        let secret = EventSecret::load().unwrap_or_else(|error| {
+            error!(
+                "Claim codes can not be checked after this session: {}",
+                error
+            );
+            EventSecret::random()
+        });
+        app.insert_resource(secret);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EventSecret(Vec<u8>);
+
+impl EventSecret {
+    /// Reads the secret from [`SECRET_VARIABLE`], or from the data directory,
+    /// creating it there on first use
+    pub fn load() -> Result<Self, String> {
+        if let Some(secret) = EventSecret::from_env() {
+            return Ok(secret);
+        }
+        let path = data_file(SECRET_FILE)?;
+        if let Some(secret) = EventSecret::read(&path)? {
+            return Ok(secret);
+        }
+        let secret = EventSecret::random();
+        write_file(&path, &secret.0)?;
+        info!("Created a new event secret in {}", path.display());
+        Ok(secret)
+    }
+
+    /// Like [`EventSecret::load`], but fails instead of creating a secret. Codes
+    /// checked against a new secret would all be reported invalid.
+    pub fn load_existing() -> Result<Self, String> {
+        if let Some(secret) = EventSecret::from_env() {
+            return Ok(secret);
+        }
+        let path = data_file(SECRET_FILE)?;
+        EventSecret::read(&path)?.ok_or_else(|| {
+            format!(
+                "{} is not set and {} does not exist",
+                SECRET_VARIABLE,
+                path.display()
+            )
+        })
+    }
+
+    fn from_env() -> Option<Self> {
+        let secret = env::var(SECRET_VARIABLE).ok()?;
+        let secret = secret.trim();
+        (!secret.is_empty()).then(|| EventSecret(secret.as_bytes().to_vec()))
+    }
+
+    /// The secret kept at `path`, `None` if there is no such file
+    fn read(path: &Path) -> Result<Option<Self>, String> {
+        match fs::read_to_string(path) {
+            Ok(secret) if !secret.trim().is_empty() => {
+                Ok(Some(EventSecret(secret.trim().as_bytes().to_vec())))
+            }
+            Ok(_) => Err(format!("{} is empty", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(format!("could not read {}: {}", path.display(), error)),
+        }
+    }
+
+    fn random() -> Self {
+        let bytes: [u8; 16] = rand::thread_rng().gen();
+        EventSecret(
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+                .into_bytes(),
+        )
+    }
+
+    fn sign(&self, serial: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+        mac.update(serial.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let bits = u32::from_be_bytes([0, digest[0], digest[1], digest[2]]) >> 4;
+        encode(bits)
+    }
+
+    /// A new code with a random serial. The serial comes from `rng`, which should
+    /// not be the seeded session RNG, or replayed runs would get the same codes.
+    pub fn issue(&self, rng: &mut impl Rng) -> ClaimCode {
+        let serial = encode(rng.gen_range(0..1 << (5 * PART_LENGTH)));
+        let signature = self.sign(&serial);
+        ClaimCode { serial, signature }
+    }
+
+    /// The code written in `text` if it was signed with this secret
+    pub fn verify(&self, text: &str) -> Option<ClaimCode> {
+        let code = ClaimCode::parse(text)?;
+        (self.sign(&code.serial) == code.signature).then_some(code)
+    }
+}
+
+/// Five bits per character, most significant first
+fn encode(bits: u32) -> String {
+    (0..PART_LENGTH)
+        .rev()
+        .map(|index| ALPHABET[(bits >> (5 * index)) as usize & 31] as char)
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClaimCode {
+    serial: String,
+    signature: String,
+}
+
+impl ClaimCode {
+    /// Accepts codes typed in lower case, without the dash, or with `O`, `I` and
+    /// `L` in place of the digits they look like
+    pub fn parse(text: &str) -> Option<Self> {
+        let characters: String = text
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' '))
+            .map(|c| match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            })
+            .collect();
+        if characters.len() != 2 * PART_LENGTH
+            || !characters.bytes().all(|byte| ALPHABET.contains(&byte))
+        {
+            return None;
+        }
+        let (serial, signature) = characters.split_at(PART_LENGTH);
+        Some(ClaimCode {
+            serial: serial.to_string(),
+            signature: signature.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ClaimCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.serial, self.signature)
+    }
+}
+
+/// Issues a code that was not handed out before and logs it with the run it was
+/// won in. `None` if the log can not be written, as such a code could be issued
+/// again.
+pub fn issue_claim_code(secret: &EventSecret, score: f32, seed: u64) -> Option<ClaimCode> {
+    let issued = match logged_codes(ISSUED_FILE) {
+        Ok(issued) => issued,
+        Err(error) => {
+            error!("Not issuing a claim code: {}", error);
+            return None;
+        }
+    };
+    let mut rng = rand::thread_rng();
+    let code = (0..MAX_ATTEMPTS)
+        .map(|_| secret.issue(&mut rng))
+        .find(|code| !issued.contains(&code.to_string()))?;
+
+    let line = format!("{}\t{}\t{:.1}\t{}", code, unix_time(), score, seed);
+    match append_line(ISSUED_FILE, &line) {
+        Ok(()) => {
+            info!("Issued claim code {}", code);
+            Some(code)
+        }
+        Err(error) => {
+            error!("Not issuing claim code {}: {}", code, error);
+            None
+        }
+    }
+}
+
+/// Codes issued so far on this machine
+pub fn issued_count() -> Result<usize, String> {
+    Ok(logged_codes(ISSUED_FILE)?.len())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Redemption {
+    /// Signed with the event secret and not redeemed before
+    Valid { issued_here: bool },
+    /// Redeemed before, at this Unix time
+    AlreadyRedeemed { at: u64 },
+    /// Mistyped, or not a code of this event
+    Invalid,
+}
+
+/// Checks `text` against the event secret and the redeemed codes, marking it as
+/// redeemed if it is valid and `redeem` is set
+pub fn check_claim_code(
+    secret: &EventSecret,
+    text: &str,
+    redeem: bool,
+) -> Result<Redemption, String> {
+    let Some(code) = secret.verify(text) else {
+        return Ok(Redemption::Invalid);
+    };
+    let code = code.to_string();
+
+    let redeemed_at = read_log(REDEEMED_FILE)?.into_iter().find_map(|fields| {
+        (fields.first() == Some(&code))
+            .then(|| fields.get(1).and_then(|at| at.parse().ok()).unwrap_or(0))
+    });
+    if let Some(at) = redeemed_at {
+        return Ok(Redemption::AlreadyRedeemed { at });
+    }
+
+    if redeem {
+        append_line(REDEEMED_FILE, &format!("{}\t{}", code, unix_time()))?;
+    }
+    Ok(Redemption::Valid {
+        issued_here: logged_codes(ISSUED_FILE)?.contains(&code),
+    })
+}
+
+fn data_file(name: &str) -> Result<PathBuf, String> {
+    storage::data_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| "there is no data directory".to_string())
+}
+
+fn write_file(path: &PathBuf, contents: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    fs::write(path, contents)
+        .map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
+
+/// The tab separated fields of every line of a log, empty if there is no log yet
+fn read_log(name: &str) -> Result<Vec<Vec<String>>, String> {
+    let path = data_file(name)?;
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(format!("could not read {}: {}", path.display(), error)),
+    }
+}
+
+fn logged_codes(name: &str) -> Result<HashSet<String>, String> {
+    Ok(read_log(name)?
+        .into_iter()
+        .filter_map(|fields| fields.into_iter().next())
+        .collect())
+}
+
+fn append_line(name: &str, line: &str) -> Result<(), String> {
+    let path = data_file(name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_time() -> u64 {
+    0
+}
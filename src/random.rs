@@ -0,0 +1,101 @@
+use crate::GameState;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::env;
+use std::ops::{Deref, DerefMut};
+
+/// Environment variable that can be used instead of the `--seed` argument
+const SEED_VARIABLE: &str = "FLAMINGO_SEED";
+
+pub struct RandomPlugin;
+
+/// Gives every session its own [`SessionRng`]. A session starts when the player
+/// leaves the controls screen.
+impl Plugin for RandomPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(seed) = configured_seed() {
+            app.insert_resource(FixedSeed(seed));
+        }
+        app.init_resource::<SessionRng>()
+            .add_system(reseed.in_schedule(OnExit(GameState::Controls)));
+    }
+}
+
+/// Seed every session starts from instead of a random one, so a reported run can
+/// be played again. Set with `--seed <n>` or the `FLAMINGO_SEED` environment variable.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct FixedSeed(pub u64);
+
+/// The random number generator of the current play session. Every random decision
+/// in the game draws from this instead of `thread_rng()`.
+#[derive(Resource)]
+pub struct SessionRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Deref for SessionRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rng
+    }
+}
+
+impl DerefMut for SessionRng {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rng
+    }
+}
+
+impl SessionRng {
+    pub fn seeded(seed: u64) -> Self {
+        SessionRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for SessionRng {
+    fn default() -> Self {
+        SessionRng::seeded(thread_rng().gen())
+    }
+}
+
+fn reseed(mut commands: Commands, fixed_seed: Option<Res<FixedSeed>>) {
+    let rng = match fixed_seed {
+        Some(fixed_seed) => SessionRng::seeded(fixed_seed.0),
+        None => SessionRng::default(),
+    };
+    info!("Starting session with seed {}", rng.seed());
+    commands.insert_resource(rng);
+}
+
+/// Reads the seed from `--seed <n>` (or `--seed=<n>`), falling back to the
+/// `FLAMINGO_SEED` environment variable.
+fn configured_seed() -> Option<u64> {
+    let mut args = env::args().skip(1);
+    let mut value = None;
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            value = args.next();
+        } else if let Some(seed) = arg.strip_prefix("--seed=") {
+            value = Some(seed.to_string());
+        }
+    }
+    let value = value.or_else(|| env::var(SEED_VARIABLE).ok())?;
+
+    match value.trim().parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            warn!("Ignoring seed {:?}, it is not a whole number", value);
+            None
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::loading::GameRulesAssets;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::reflect::erased_serde::__private::serde;
+use bevy::reflect::TypeUuid;
+
+pub struct RulesPlugin;
+
+/// Replaces the default [`GameRules`] with the ones from `data/event.rules.ron`
+/// once loading is done
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRules>()
+            .add_system(apply_game_rules.in_schedule(OnExit(GameState::Loading)));
+    }
+}
+
+/// How a session is won and lost
+#[derive(serde::Deserialize, TypeUuid, Resource, Clone, Debug)]
+#[uuid = "0d7e8f5b-3a0c-4f7e-b1c6-5e2a9d4f7c13"]
+pub struct GameRules {
+    /// Correct answers needed to win
+    #[serde(default = "default_target_score")]
+    pub target_score: f32,
+    /// Seconds on the clock before the game is lost, for levels that do not
+    /// set their own `time_limit`
+    #[serde(default = "default_time_limit")]
+    pub time_limit: f32,
+    /// Seconds taken off the clock for every wrong answer
+    #[serde(default)]
+    pub wrong_answer_penalty: f32,
+    /// The clock flashes once this many seconds or fewer are left, for levels
+    /// that do not set their own `warning_at`
+    #[serde(default = "default_warning_at")]
+    pub warning_at: f32,
+    /// Hearts the player starts with
+    #[serde(default = "default_lives")]
+    pub lives: f32,
+}
+
+fn default_target_score() -> f32 {
+    5.
+}
+
+fn default_time_limit() -> f32 {
+    120.
+}
+
+fn default_warning_at() -> f32 {
+    31.
+}
+
+fn default_lives() -> f32 {
+    3.
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            target_score: default_target_score(),
+            time_limit: default_time_limit(),
+            wrong_answer_penalty: 0.,
+            warning_at: default_warning_at(),
+            lives: default_lives(),
+        }
+    }
+}
+
+fn apply_game_rules(
+    mut commands: Commands,
+    rules_assets: Res<GameRulesAssets>,
+    rules: Res<Assets<GameRules>>,
+) {
+    let rules = rules.get(&rules_assets.rules).cloned().unwrap_or_default();
+    info!(
+        "Playing to {} correct answer(s) in {}s with {} heart(s)",
+        rules.target_score, rules.time_limit, rules.lives
+    );
+    commands.insert_resource(rules);
+}
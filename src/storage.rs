@@ -0,0 +1,8 @@
+use directories::ProjectDirs;
+use std::path::PathBuf;
+
+/// Where the game keeps files that outlive a session, such as high scores and
+/// issued prize codes. `None` on platforms without a home directory.
+pub fn data_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "Flamin-Go").map(|dirs| dirs.data_dir().to_path_buf())
+}
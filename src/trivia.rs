@@ -0,0 +1,397 @@
+//! Question packs written as spreadsheets: `*.trivia.csv` files, or `*.trivia.json`
+//! files holding an array of row objects.
+//!
+//! Each row is one question. Column names are case-insensitive:
+//!
+//! | column | |
+//! |---|---|
+//! | `key` | unique name of the question, required |
+//! | `description` | the question text, required |
+//! | `kind` | `SingleChoice` (default), `TrueFalse`, `SelectAll`, `Ordering` or `Numeric` |
+//! | `options` | options separated by `\|` |
+//! | `answer` | the correct option; for `SelectAll` every correct option, separated by `\|` |
+//! | `min`, `max`, `step`, `tolerance` | the range of a `Numeric` question |
+//! | `category`, `difficulty`, `explanation`, `source`, `image` | as in `*.game-data.ron` packs |
+//! | `tags` | tags separated by `\|` |
+//! | `time_limit` | seconds to answer, overriding the console's `answer_time` |
+//!
+//! A malformed row is reported with its row number and skipped; the rest of the
+//! sheet still loads.
+
+use crate::loading::{Difficulty, GameDataAsset, GameDataAssetDynamicCollection, QuestionKind};
+use bevy::asset::{AssetLoader, BoxedFuture, Error, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+pub const CSV_EXTENSION: &str = "trivia.csv";
+pub const JSON_EXTENSION: &str = "trivia.json";
+
+/// Separates the items of list columns such as `options` and `tags`
+const LIST_SEPARATOR: char = '|';
+
+/// One spreadsheet row, keyed by lower-case column name
+type Row = HashMap<String, String>;
+
+/// Every row of a sheet with its row number, or why it could not be read
+type Rows = Vec<(usize, Result<Row, String>)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaFormat {
+    Csv,
+    Json,
+}
+
+impl TriviaFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name.ends_with(CSV_EXTENSION) {
+            Some(TriviaFormat::Csv)
+        } else if name.ends_with(JSON_EXTENSION) {
+            Some(TriviaFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RowError {
+    /// Row as numbered by spreadsheet programs; for CSV the header is row 1
+    pub row: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.message)
+    }
+}
+
+/// The questions of a sheet in row order, and every row that could not be read
+#[derive(Debug, Default)]
+pub struct TriviaSheet {
+    pub questions: Vec<(String, GameDataAsset)>,
+    pub errors: Vec<RowError>,
+}
+
+/// Fails only if the file as a whole can not be read, e.g. a CSV file without a
+/// header or JSON that is not an array of objects.
+pub fn parse_sheet(format: TriviaFormat, bytes: &[u8]) -> Result<TriviaSheet, String> {
+    let rows = match format {
+        TriviaFormat::Csv => csv_rows(bytes)?,
+        TriviaFormat::Json => json_rows(bytes)?,
+    };
+
+    let mut sheet = TriviaSheet::default();
+    let mut first_rows: HashMap<String, usize> = HashMap::new();
+    for (row, fields) in rows {
+        let question = fields.and_then(|fields| question_from_row(&fields));
+        match question {
+            Ok((key, question)) => {
+                if let Some(first_row) = first_rows.get(&key) {
+                    sheet.errors.push(RowError {
+                        row,
+                        message: format!("key {:?} is already used on row {}", key, first_row),
+                    });
+                } else {
+                    first_rows.insert(key.clone(), row);
+                    sheet.questions.push((key, question));
+                }
+            }
+            Err(message) => sheet.errors.push(RowError { row, message }),
+        }
+    }
+    Ok(sheet)
+}
+
+fn csv_rows(bytes: &[u8]) -> Result<Rows, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|error| format!("could not read the header row: {}", error))?
+        .iter()
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        match record {
+            Ok(record) => {
+                let row = record
+                    .position()
+                    .map_or(0, |position| position.line() as usize);
+                let fields = headers
+                    .iter()
+                    .cloned()
+                    .zip(record.iter().map(str::to_string));
+                rows.push((row, Ok(fields.collect())));
+            }
+            Err(error) => {
+                let row = error
+                    .position()
+                    .map_or(0, |position| position.line() as usize);
+                let message = match error.kind() {
+                    csv::ErrorKind::UnequalLengths {
+                        expected_len, len, ..
+                    } => format!("has {} column(s), the header has {}", len, expected_len),
+                    _ => error.to_string(),
+                };
+                rows.push((row, Err(message)));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn json_rows(bytes: &[u8]) -> Result<Rows, String> {
+    let entries: Vec<Value> = serde_json::from_slice(bytes)
+        .map_err(|error| format!("expected an array of rows: {}", error))?;
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let fields = match entry {
+                Value::Object(fields) => Ok(fields
+                    .into_iter()
+                    .filter_map(|(column, value)| {
+                        json_cell(value).map(|value| (column.to_lowercase(), value))
+                    })
+                    .collect()),
+                _ => Err("is not an object".to_string()),
+            };
+            (index + 1, fields)
+        })
+        .collect())
+}
+
+/// Spreadsheet exports are not picky about types, so numbers, booleans and
+/// arrays are accepted wherever text is expected.
+fn json_cell(value: Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text),
+        Value::Array(items) => Some(
+            items
+                .into_iter()
+                .filter_map(json_cell)
+                .collect::<Vec<_>>()
+                .join(&LIST_SEPARATOR.to_string()),
+        ),
+        other => Some(other.to_string()),
+    }
+}
+
+fn question_from_row(row: &Row) -> Result<(String, GameDataAsset), String> {
+    let field = |column: &str| {
+        row.get(column)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let text = |column: &str| field(column).map(str::to_string);
+    let list = |column: &str| {
+        field(column).map_or_else(Vec::new, |value| {
+            value
+                .split(LIST_SEPARATOR)
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+    };
+    let number = |column: &str| {
+        field(column)
+            .map(|value| {
+                value
+                    .parse::<f64>()
+                    .map_err(|_| format!("{} {:?} is not a number", column, value))
+            })
+            .transpose()
+    };
+
+    let key = text("key").ok_or("has no key")?;
+    let description = text("description").ok_or("has no description")?;
+    let mut answer = text("answer").unwrap_or_default();
+
+    let kind = match field("kind").map(normalize).as_deref() {
+        None | Some("singlechoice") => QuestionKind::SingleChoice,
+        Some("truefalse") => QuestionKind::TrueFalse,
+        Some("selectall") => QuestionKind::SelectAll {
+            answers: list("answer"),
+        },
+        Some("ordering") => QuestionKind::Ordering,
+        Some("numeric") => QuestionKind::Numeric {
+            min: number("min")?.ok_or("numeric question has no min")?,
+            max: number("max")?.ok_or("numeric question has no max")?,
+            step: number("step")?.unwrap_or(1.),
+            tolerance: number("tolerance")?.unwrap_or(0.),
+        },
+        Some(_) => return Err(format!("unknown kind {:?}", field("kind").unwrap_or(""))),
+    };
+    if let QuestionKind::SelectAll { .. } = kind {
+        answer.clear();
+    }
+
+    let difficulty = match field("difficulty").map(normalize).as_deref() {
+        None => None,
+        Some("easy") => Some(Difficulty::Easy),
+        Some("medium") => Some(Difficulty::Medium),
+        Some("hard") => Some(Difficulty::Hard),
+        Some(_) => {
+            return Err(format!(
+                "unknown difficulty {:?}",
+                field("difficulty").unwrap_or("")
+            ))
+        }
+    };
+
+    let question = GameDataAsset::Question {
+        description,
+        kind,
+        options: list("options"),
+        answer,
+        category: text("category"),
+        difficulty,
+        explanation: text("explanation"),
+        source: text("source"),
+        tags: list("tags"),
+        image: text("image"),
+        time_limit: number("time_limit")?.map(|seconds| seconds as f32),
+    };
+    Ok((key, question))
+}
+
+/// Lets sheets spell `SelectAll` as "select all", "select_all" or "Select-All"
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Loads spreadsheet packs into the same [`GameDataAssetDynamicCollection`] as
+/// `*.game-data.ron` packs, logging every row it has to skip.
+#[derive(Default)]
+pub struct TriviaLoader;
+
+impl AssetLoader for TriviaLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let path = load_context.path();
+            let format = TriviaFormat::from_path(path).unwrap_or(TriviaFormat::Csv);
+            let sheet = parse_sheet(format, bytes)
+                .map_err(|message| Error::msg(format!("{}: {}", path.display(), message)))?;
+            for error in &sheet.errors {
+                error!("Skipping {} {}", path.display(), error);
+            }
+            let collection = GameDataAssetDynamicCollection(sheet.questions.into_iter().collect());
+            load_context.set_default_asset(LoadedAsset::new(collection));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[CSV_EXTENSION, JSON_EXTENSION]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_csv_row() {
+        let sheet = parse_sheet(
+            TriviaFormat::Csv,
+            b"Key,Description,Options,Answer,Difficulty,Tags\n\
+              pong,First arcade hit?,Pong | Tank,Pong,easy,atari|1970s\n",
+        )
+        .unwrap();
+
+        assert!(sheet.errors.is_empty(), "{:?}", sheet.errors);
+        let [(key, question)] = sheet.questions.as_slice() else {
+            panic!("expected one question, got {:?}", sheet.questions);
+        };
+        let GameDataAsset::Question {
+            description,
+            options,
+            answer,
+            difficulty,
+            tags,
+            ..
+        } = question;
+        assert_eq!(key, "pong");
+        assert_eq!(description, "First arcade hit?");
+        assert_eq!(options, &["Pong", "Tank"]);
+        assert_eq!(answer, "Pong");
+        assert_eq!(*difficulty, Some(Difficulty::Easy));
+        assert_eq!(tags, &["atari", "1970s"]);
+    }
+
+    #[test]
+    fn reads_a_json_row() {
+        let sheet = parse_sheet(
+            TriviaFormat::Json,
+            br#"[{"key": "year", "description": "Pong came out in?", "kind": "Numeric",
+                  "min": 1960, "max": 1990, "answer": 1972}]"#,
+        )
+        .unwrap();
+
+        assert!(sheet.errors.is_empty(), "{:?}", sheet.errors);
+        let [(key, GameDataAsset::Question { kind, answer, .. })] = sheet.questions.as_slice()
+        else {
+            panic!("expected one question, got {:?}", sheet.questions);
+        };
+        assert_eq!(key, "year");
+        assert_eq!(answer, "1972");
+        assert_eq!(
+            *kind,
+            QuestionKind::Numeric {
+                min: 1960.,
+                max: 1990.,
+                step: 1.,
+                tolerance: 0.,
+            }
+        );
+    }
+
+    #[test]
+    fn skips_malformed_rows_with_their_row_number() {
+        let sheet = parse_sheet(
+            TriviaFormat::Csv,
+            b"key,description,options,answer,difficulty\n\
+              ok,Fine?,Yes|No,Yes,\n\
+              hard,Too hard?,Yes|No,Yes,impossible\n\
+              ,No key?,Yes|No,Yes,\n\
+              short,Too few columns\n",
+        )
+        .unwrap();
+
+        assert_eq!(sheet.questions.len(), 1);
+        let errors: Vec<String> = sheet.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "row 3: unknown difficulty \"impossible\"",
+                "row 4: has no key",
+                "row 5: has 2 column(s), the header has 5",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_json_that_is_not_an_array() {
+        let error = parse_sheet(TriviaFormat::Json, br#"{"key": "pong"}"#).unwrap_err();
+        assert!(error.starts_with("expected an array of rows"), "{}", error);
+    }
+}
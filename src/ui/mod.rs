@@ -1,5 +1,8 @@
-use crate::clock::TimeSinceLevelStart;
+use crate::clock::{LevelClock, TimeSinceLevelStart};
+use crate::lifelines::{Lifeline, Lifelines};
 use crate::loading::{FontAssets, TextureAssets};
+use crate::player::Lives;
+use crate::rules::GameRules;
 use crate::GameState;
 use bevy::prelude::*;
 
@@ -13,25 +16,49 @@ pub struct GameUiClock;
 pub struct GameUiScore;
 
 #[derive(Resource, Default)]
-pub struct Score(pub(crate) f32);
+pub struct Score {
+    /// Questions answered correctly
+    pub(crate) correct: f32,
+    /// Extra points for answering quickly, kept apart so it never counts
+    /// towards the questions needed to win
+    pub(crate) speed_bonus: f32,
+}
 
 #[derive(Component)]
 struct ScoreText;
 
+#[derive(Component, Default, Clone, Debug)]
+pub struct GameUiLifelines;
+
+#[derive(Component)]
+struct LifelineText(Lifeline);
+
+#[derive(Component, Default, Clone, Debug)]
+pub struct GameUiLives;
+
+/// The `n`th heart of the HUD, counting from zero
+#[derive(Component)]
+struct HeartIcon(usize);
+
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems((spawn_clock, spawn_score).in_schedule(OnEnter(GameState::Playing)))
-            .add_systems(
-                (
-                    update_counter,
-                    update_score_text,
-                    // death_screen,
-                )
-                    .in_set(OnUpdate(GameState::Playing)),
+        app.add_systems(
+            (spawn_clock, spawn_score, spawn_lives, spawn_lifelines)
+                .in_schedule(OnEnter(GameState::Playing)),
+        )
+        .add_systems(
+            (
+                update_counter,
+                update_score_text,
+                update_lifeline_text,
+                update_hearts,
+                // death_screen,
             )
-            .add_system(cleanup.in_schedule(OnExit(GameState::Playing)));
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(cleanup.in_schedule(OnExit(GameState::Playing)));
     }
 }
 
@@ -86,7 +113,12 @@ fn spawn_clock(mut commands: Commands, font_assets: Res<FontAssets>, assets: Res
         });
 }
 
-fn spawn_score(mut commands: Commands, font_assets: Res<FontAssets>, assets: Res<TextureAssets>) {
+fn spawn_score(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    assets: Res<TextureAssets>,
+    rules: Res<GameRules>,
+) {
     commands.init_resource::<Score>();
     commands
         .spawn(NodeBundle {
@@ -112,20 +144,20 @@ fn spawn_score(mut commands: Commands, font_assets: Res<FontAssets>, assets: Res
         })
         .insert(GameUiScore::default())
         .with_children(|parent| {
-            // Heart icon score
+            // Coin icon score
             parent.spawn(ImageBundle {
                 style: Style {
                     size: Size::new(Val::Px(8. * 5.), Val::Px(8. * 5.)),
                     ..Default::default()
                 },
-                image: assets.half_heart.clone().into(),
+                image: assets.coin.clone().into(),
                 ..Default::default()
             });
             parent
                 .spawn(TextBundle {
                     text: Text {
                         sections: vec![TextSection {
-                            value: "0/5".to_string(),
+                            value: format!("0/{:.0}", rules.target_score),
                             style: TextStyle {
                                 font: font_assets.pico.clone(),
                                 font_size: 32.0,
@@ -140,23 +172,191 @@ fn spawn_score(mut commands: Commands, font_assets: Res<FontAssets>, assets: Res
         });
 }
 
-fn update_score_text(score: Res<Score>, mut score_text: Query<&mut Text, With<ScoreText>>) {
+fn spawn_lives(mut commands: Commands, assets: Res<TextureAssets>, rules: Res<GameRules>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Auto, Val::Px(75.0)),
+                min_size: Size::width(Val::Px(250.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                gap: Size::width(Val::Px(8.)),
+                padding: UiRect::horizontal(Val::Px(16.)),
+                position: UiRect {
+                    left: Val::Px(10.),
+                    top: Val::Px(95.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            background_color: BackgroundColor(Color::Rgba {
+                red: 0.098,
+                green: 0.078,
+                blue: 0.169,
+                alpha: 1.,
+            }),
+            ..Default::default()
+        })
+        .insert(GameUiLives)
+        .with_children(|parent| {
+            for index in 0..rules.lives.ceil() as usize {
+                parent
+                    .spawn(ImageBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(8. * 5.), Val::Px(8. * 5.)),
+                            ..Default::default()
+                        },
+                        image: assets.heart.clone().into(),
+                        ..Default::default()
+                    })
+                    .insert(HeartIcon(index));
+            }
+        });
+}
+
+fn update_hearts(
+    lives: Query<&Lives, Changed<Lives>>,
+    mut hearts: Query<(&mut UiImage, &HeartIcon)>,
+    assets: Res<TextureAssets>,
+) {
+    let Ok(lives) = lives.get_single() else {
+        return;
+    };
+    for (mut image, HeartIcon(index)) in &mut hearts {
+        let left = lives.hearts - *index as f32;
+        image.texture = if left >= 1. {
+            assets.heart.clone()
+        } else if left >= 0.5 {
+            assets.half_heart.clone()
+        } else {
+            assets.empty_heart.clone()
+        };
+    }
+}
+
+fn spawn_lifelines(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    assets: Res<TextureAssets>,
+    lifelines: Res<Lifelines>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(400.0), Val::Px(75.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::SpaceAround,
+                align_items: AlignItems::Center,
+                position: UiRect {
+                    left: Val::Px(10.),
+                    bottom: Val::Px(10.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            background_color: BackgroundColor(Color::Rgba {
+                red: 0.098,
+                green: 0.078,
+                blue: 0.169,
+                alpha: 1.,
+            }),
+            ..Default::default()
+        })
+        .insert(GameUiLifelines)
+        .with_children(|parent| {
+            // Lifelines are bound to the number keys in this order
+            for (key, lifeline) in Lifeline::ALL.into_iter().enumerate() {
+                let icon = match lifeline {
+                    Lifeline::FiftyFifty => assets.coin.clone(),
+                    Lifeline::Skip => assets.potion.clone(),
+                    Lifeline::ExtraTime => assets.clock.clone(),
+                };
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            format!("{}", key + 1),
+                            TextStyle {
+                                font: font_assets.pico.clone(),
+                                font_size: 16.0,
+                                color: Color::GRAY,
+                            },
+                        ));
+                        parent.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(8. * 5.), Val::Px(8. * 5.)),
+                                ..Default::default()
+                            },
+                            image: icon.into(),
+                            ..Default::default()
+                        });
+                        parent
+                            .spawn(TextBundle::from_section(
+                                format!("x{}", lifelines.count(lifeline)),
+                                TextStyle {
+                                    font: font_assets.pico.clone(),
+                                    font_size: 24.0,
+                                    color: Color::WHITE,
+                                },
+                            ))
+                            .insert(LifelineText(lifeline));
+                    });
+            }
+        });
+}
+
+fn update_lifeline_text(
+    lifelines: Res<Lifelines>,
+    mut lifeline_texts: Query<(&mut Text, &LifelineText)>,
+) {
+    if !lifelines.is_changed() {
+        return;
+    }
+    for (mut text, LifelineText(lifeline)) in &mut lifeline_texts {
+        let count = lifelines.count(*lifeline);
+        text.sections[0].value = format!("x{}", count);
+        text.sections[0].style.color = if count == 0 {
+            Color::GRAY
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn update_score_text(
+    score: Res<Score>,
+    rules: Res<GameRules>,
+    mut score_text: Query<&mut Text, With<ScoreText>>,
+) {
     if !score.is_changed() {
         return;
     }
-    score_text.single_mut().sections[0].value = format!("{:.0}/5", score.0);
+    let mut text = format!("{:.0}/{:.0}", score.correct, rules.target_score);
+    if score.speed_bonus > 0. {
+        text += &format!(" +{:.1}", score.speed_bonus);
+    }
+    score_text.single_mut().sections[0].value = text;
 }
 
 fn update_counter(
     mut timer_ui: Query<&mut Text, With<Counter>>,
     time_since_level_start: Res<TimeSinceLevelStart>,
+    level_clock: Res<LevelClock>,
     asset_holder: Res<FontAssets>,
 ) {
     for mut text in timer_ui.iter_mut() {
-        let remaining = 120. - time_since_level_start.0;
+        let remaining = level_clock.time_left(time_since_level_start.0);
         let minutes = (remaining / 60.0) as u32;
         let seconds = (remaining % 60.0) as u32;
-        let color = if remaining <= 31.0 {
+        let color = if remaining <= level_clock.warning_at {
             if seconds % 2 == 0 {
                 Color::hex("FF004D").unwrap()
             } else {
@@ -181,6 +381,8 @@ fn cleanup(
     mut commands: Commands,
     clock_ui_entity: Query<Entity, With<GameUiClock>>,
     score_ui_entity: Query<Entity, With<GameUiScore>>,
+    lifeline_ui_entity: Query<Entity, With<GameUiLifelines>>,
+    lives_ui_entity: Query<Entity, With<GameUiLives>>,
 ) {
     for entity in clock_ui_entity.iter() {
         commands.entity(entity).despawn_recursive();
@@ -188,4 +390,10 @@ fn cleanup(
     for entity in score_ui_entity.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    for entity in lifeline_ui_entity.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in lives_ui_entity.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
@@ -0,0 +1,535 @@
+//! Offline checks for `*.game-data.ron`, `*.trivia.csv` and `*.trivia.json` question packs.
+//!
+//! The game itself only notices a broken question once a player is shown it, so this
+//! module parses packs with the same [`GameDataAsset`] types used at load time and
+//! reports every problem it finds up front. See the `validate-packs` binary.
+
+use crate::console::ChoiceLayout;
+use crate::loading::{GameDataAsset, QuestionKind};
+use crate::trivia::{self, TriviaFormat, CSV_EXTENSION, JSON_EXTENSION};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory the game loads its question packs from
+pub const DEFAULT_PACK_DIR: &str = "assets/data/packs";
+
+pub const PACK_EXTENSIONS: [&str; 3] = ["game-data.ron", CSV_EXTENSION, JSON_EXTENSION];
+
+/// Directory question image paths are relative to
+pub const ASSET_DIR: &str = "assets";
+
+pub const MIN_OPTIONS: usize = 2;
+pub const MAX_OPTIONS: usize = 6;
+/// Below an image only two rows of answer buttons fit into the console
+pub const MAX_IMAGE_OPTIONS: usize = 4;
+
+/// Answer buttons use the 20px pixel font, so each glyph is about 20px wide
+const OPTION_GLYPH_WIDTH: f32 = 20.;
+const OPTION_BUTTON_PADDING: f32 = 40.;
+
+/// The question text wraps at 1000px in the 18px pixel font; more than four
+/// lines runs into the answer buttons.
+pub const MAX_DESCRIPTION_CHARS: usize = 220;
+
+#[derive(Debug)]
+pub struct PackError {
+    pub path: PathBuf,
+    /// The question the error belongs to, if it could be attributed to one
+    pub key: Option<String>,
+    pub kind: PackErrorKind,
+}
+
+#[derive(Debug)]
+pub enum PackErrorKind {
+    Io(std::io::Error),
+    Parse(String),
+    /// A spreadsheet row that could not be turned into a question
+    Row(trivia::RowError),
+    DuplicateKey {
+        first_defined_in: PathBuf,
+    },
+    OptionCount(usize),
+    DuplicateOption(String),
+    AnswerNotInOptions(String),
+    OptionTooLong {
+        option: String,
+        max: usize,
+    },
+    DescriptionTooLong(usize),
+    NoAnswers,
+    InvalidNumber(String),
+    InvalidRange {
+        min: f64,
+        max: f64,
+        step: f64,
+    },
+    NumberOutOfRange(f64),
+    ImageNotFound(String),
+    TooManyOptionsForImage(usize),
+    InvalidTimeLimit(f32),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(key) = &self.key {
+            write!(f, " [{}]", key)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl fmt::Display for PackErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackErrorKind::Io(error) => write!(f, "could not read file: {}", error),
+            PackErrorKind::Parse(message) => write!(f, "could not parse pack: {}", message),
+            PackErrorKind::Row(error) => write!(f, "{}", error),
+            PackErrorKind::DuplicateKey { first_defined_in } => write!(
+                f,
+                "key is already defined in {}",
+                first_defined_in.display()
+            ),
+            PackErrorKind::OptionCount(count) => write!(
+                f,
+                "has {} option(s), expected between {} and {}",
+                count, MIN_OPTIONS, MAX_OPTIONS
+            ),
+            PackErrorKind::DuplicateOption(option) => {
+                write!(f, "option {:?} is listed more than once", option)
+            }
+            PackErrorKind::AnswerNotInOptions(answer) => {
+                write!(f, "answer {:?} is not one of the options", answer)
+            }
+            PackErrorKind::OptionTooLong { option, max } => write!(
+                f,
+                "option {:?} is longer than {} characters and will overflow its button",
+                option, max
+            ),
+            PackErrorKind::DescriptionTooLong(length) => write!(
+                f,
+                "description is {} characters long, the console fits {}",
+                length, MAX_DESCRIPTION_CHARS
+            ),
+            PackErrorKind::NoAnswers => write!(f, "select-all question lists no answers"),
+            PackErrorKind::InvalidNumber(answer) => {
+                write!(f, "answer {:?} is not a number", answer)
+            }
+            PackErrorKind::InvalidRange { min, max, step } => write!(
+                f,
+                "numeric range {}..={} with step {} is empty",
+                min, max, step
+            ),
+            PackErrorKind::NumberOutOfRange(answer) => {
+                write!(
+                    f,
+                    "answer {} can not be dialled in with the given range",
+                    answer
+                )
+            }
+            PackErrorKind::ImageNotFound(image) => {
+                write!(f, "image {:?} does not exist in {}", image, ASSET_DIR)
+            }
+            PackErrorKind::TooManyOptionsForImage(count) => write!(
+                f,
+                "has {} button(s) below its image, the console fits {}",
+                count, MAX_IMAGE_OPTIONS
+            ),
+            PackErrorKind::InvalidTimeLimit(seconds) => {
+                write!(f, "time limit of {} seconds is not positive", seconds)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub packs: usize,
+    pub questions: usize,
+    pub errors: Vec<PackError>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Validates every pack in `paths`. Directories are searched (non-recursively)
+/// for files ending in one of the [`PACK_EXTENSIONS`].
+pub fn validate_packs<P: AsRef<Path>>(paths: &[P]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut seen_keys: HashMap<String, PathBuf> = HashMap::new();
+
+    for path in paths {
+        let files = match pack_files(path.as_ref()) {
+            Ok(files) => files,
+            Err(error) => {
+                report.errors.push(PackError {
+                    path: path.as_ref().to_path_buf(),
+                    key: None,
+                    kind: PackErrorKind::Io(error),
+                });
+                continue;
+            }
+        };
+
+        for file in files {
+            validate_pack(&file, &mut seen_keys, &mut report);
+        }
+    }
+
+    report
+}
+
+fn pack_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let file = entry?.path();
+        let is_pack = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                PACK_EXTENSIONS
+                    .iter()
+                    .any(|extension| name.ends_with(extension))
+            });
+        if file.is_file() && is_pack {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn validate_pack(
+    path: &Path,
+    seen_keys: &mut HashMap<String, PathBuf>,
+    report: &mut ValidationReport,
+) {
+    let error = |key: Option<&str>, kind| PackError {
+        path: path.to_path_buf(),
+        key: key.map(str::to_string),
+        kind,
+    };
+
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(io_error) => {
+            report.errors.push(error(None, PackErrorKind::Io(io_error)));
+            return;
+        }
+    };
+    let parsed = match TriviaFormat::from_path(path) {
+        Some(format) => trivia::parse_sheet(format, &contents).map(|sheet| {
+            for row_error in sheet.errors {
+                report
+                    .errors
+                    .push(error(None, PackErrorKind::Row(row_error)));
+            }
+            sheet.questions
+        }),
+        None => String::from_utf8(contents)
+            .map_err(|utf8_error| utf8_error.to_string())
+            .and_then(|contents| {
+                ron::from_str::<PackEntries>(&contents)
+                    .map_err(|parse_error| parse_error.to_string())
+            })
+            .map(|PackEntries(entries)| entries),
+    };
+    let entries = match parsed {
+        Ok(entries) => entries,
+        Err(message) => {
+            report
+                .errors
+                .push(error(None, PackErrorKind::Parse(message)));
+            return;
+        }
+    };
+
+    report.packs += 1;
+    for (key, asset) in entries {
+        report.questions += 1;
+        if let Some(first_defined_in) = seen_keys.get(&key) {
+            let kind = PackErrorKind::DuplicateKey {
+                first_defined_in: first_defined_in.clone(),
+            };
+            report.errors.push(error(Some(&key), kind));
+        } else {
+            seen_keys.insert(key.clone(), path.to_path_buf());
+        }
+        for kind in validate_question(&asset) {
+            report.errors.push(error(Some(&key), kind));
+        }
+    }
+}
+
+fn validate_question(asset: &GameDataAsset) -> Vec<PackErrorKind> {
+    let mut errors = Vec::new();
+
+    match asset {
+        GameDataAsset::Question {
+            description,
+            kind,
+            options,
+            answer,
+            image,
+            time_limit,
+            ..
+        } => {
+            if let Some(seconds) = *time_limit {
+                if seconds <= 0. {
+                    errors.push(PackErrorKind::InvalidTimeLimit(seconds));
+                }
+            }
+
+            if let Some(image) = image {
+                if !Path::new(ASSET_DIR).join(image).is_file() {
+                    errors.push(PackErrorKind::ImageNotFound(image.clone()));
+                }
+            }
+
+            let description_length = description.chars().count();
+            if description_length > MAX_DESCRIPTION_CHARS {
+                errors.push(PackErrorKind::DescriptionTooLong(description_length));
+            }
+
+            if let QuestionKind::Numeric { min, max, step, .. } = *kind {
+                validate_numeric(answer, min, max, step, &mut errors);
+                return errors;
+            }
+
+            let options = kind.options_or_default(options);
+            let slots = options.len() + usize::from(kind.has_submit_button());
+            let layout = if image.is_some() {
+                if slots > MAX_IMAGE_OPTIONS {
+                    errors.push(PackErrorKind::TooManyOptionsForImage(slots));
+                }
+                ChoiceLayout::for_image_options(slots)
+            } else {
+                ChoiceLayout::for_options(slots)
+            };
+            validate_options(&options, layout, &mut errors);
+
+            match kind {
+                QuestionKind::SingleChoice | QuestionKind::TrueFalse => {
+                    if !options.contains(answer) {
+                        errors.push(PackErrorKind::AnswerNotInOptions(answer.clone()));
+                    }
+                }
+                QuestionKind::SelectAll { answers } => {
+                    if answers.is_empty() {
+                        errors.push(PackErrorKind::NoAnswers);
+                    }
+                    for answer in answers.iter().filter(|answer| !options.contains(answer)) {
+                        errors.push(PackErrorKind::AnswerNotInOptions(answer.clone()));
+                    }
+                }
+                QuestionKind::Ordering | QuestionKind::Numeric { .. } => {}
+            }
+        }
+    }
+
+    errors
+}
+
+fn validate_options(options: &[String], layout: ChoiceLayout, errors: &mut Vec<PackErrorKind>) {
+    if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+        errors.push(PackErrorKind::OptionCount(options.len()));
+    }
+
+    let max_chars = max_option_chars(layout);
+    for (index, option) in options.iter().enumerate() {
+        if options[..index].contains(option) {
+            errors.push(PackErrorKind::DuplicateOption(option.clone()));
+        }
+        if option.chars().count() > max_chars {
+            errors.push(PackErrorKind::OptionTooLong {
+                option: option.clone(),
+                max: max_chars,
+            });
+        }
+    }
+}
+
+fn validate_numeric(answer: &str, min: f64, max: f64, step: f64, errors: &mut Vec<PackErrorKind>) {
+    if !QuestionKind::is_valid_range(min, max, step) {
+        errors.push(PackErrorKind::InvalidRange { min, max, step });
+    }
+    match answer.trim().parse::<f64>() {
+        Ok(number) if number < min || number > max => {
+            errors.push(PackErrorKind::NumberOutOfRange(number))
+        }
+        Ok(_) => {}
+        Err(_) => errors.push(PackErrorKind::InvalidNumber(answer.to_string())),
+    }
+}
+
+/// How many characters fit on an answer button, which narrows once the console
+/// switches to two columns.
+fn max_option_chars(layout: ChoiceLayout) -> usize {
+    ((layout.button_width() - OPTION_BUTTON_PADDING) / OPTION_GLYPH_WIDTH) as usize
+}
+
+/// The entries of a pack in file order. Deserializing into a map, like the game does,
+/// would silently keep only the last of two entries sharing a key.
+struct PackEntries(Vec<(String, GameDataAsset)>);
+
+impl<'de> Deserialize<'de> for PackEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = PackEntries;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of question keys to questions")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                deserializer.deserialize_map(self)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(PackEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("", EntriesVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Writes `files` into a fresh directory and validates it
+    fn validate(files: &[(&str, &str)]) -> ValidationReport {
+        static DIRS: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "validate-packs-{}-{}",
+            std::process::id(),
+            DIRS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        let report = validate_packs(&[&dir]);
+        fs::remove_dir_all(&dir).unwrap();
+        report
+    }
+
+    fn question_errors(question: &str) -> Vec<PackErrorKind> {
+        let pack = format!("({{ \"question\": {} }})", question);
+        validate(&[("test.game-data.ron", &pack)])
+            .errors
+            .into_iter()
+            .map(|error| error.kind)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_valid_question() {
+        let errors =
+            question_errors(r#"Question(description: "2 + 2?", options: ["3", "4"], answer: "4")"#);
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn reports_a_key_defined_in_two_packs() {
+        let question = r#"Question(description: "2 + 2?", options: ["3", "4"], answer: "4")"#;
+        let pack = format!("({{ \"sum\": {} }})", question);
+        let report = validate(&[("a.game-data.ron", &pack), ("b.game-data.ron", &pack)]);
+
+        assert_eq!(report.packs, 2);
+        assert_eq!(report.errors.len(), 1);
+        let error = &report.errors[0];
+        assert!(error.path.ends_with("b.game-data.ron"));
+        assert_eq!(error.key.as_deref(), Some("sum"));
+        assert!(matches!(
+            &error.kind,
+            PackErrorKind::DuplicateKey { first_defined_in } if first_defined_in.ends_with("a.game-data.ron")
+        ));
+    }
+
+    #[test]
+    fn reports_an_answer_that_is_not_an_option() {
+        let errors =
+            question_errors(r#"Question(description: "2 + 2?", options: ["3", "5"], answer: "4")"#);
+        assert!(matches!(
+            errors.as_slice(),
+            [PackErrorKind::AnswerNotInOptions(answer)] if answer == "4"
+        ));
+    }
+
+    #[test]
+    fn reports_numeric_bounds() {
+        let errors = question_errors(
+            r#"Question(description: "Year?", kind: Numeric(min: 2000, max: 1900), answer: "1950")"#,
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                PackErrorKind::InvalidRange { .. },
+                PackErrorKind::NumberOutOfRange(_)
+            ]
+        ));
+
+        let errors = question_errors(
+            r#"Question(description: "Year?", kind: Numeric(min: 1900, max: 2000, step: 0), answer: "1950")"#,
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [PackErrorKind::InvalidRange { step, .. }] if *step == 0.
+        ));
+
+        let errors = question_errors(
+            r#"Question(description: "Year?", kind: Numeric(min: 1900, max: 2000), answer: "2001")"#,
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [PackErrorKind::NumberOutOfRange(answer)] if *answer == 2001.
+        ));
+    }
+
+    #[test]
+    fn reports_a_missing_image() {
+        let errors = question_errors(
+            r#"Question(description: "Who?", options: ["A", "B"], answer: "A", image: Some("images/missing.png"))"#,
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [PackErrorKind::ImageNotFound(image)] if image == "images/missing.png"
+        ));
+    }
+
+    #[test]
+    fn reports_a_pack_that_does_not_parse() {
+        let report = validate(&[("broken.game-data.ron", "({ \"question\": Question(")]);
+        assert_eq!(report.packs, 0);
+        assert!(matches!(
+            report.errors.as_slice(),
+            [PackError {
+                kind: PackErrorKind::Parse(_),
+                key: None,
+                ..
+            }]
+        ));
+    }
+}